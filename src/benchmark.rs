@@ -0,0 +1,220 @@
+// A small statistical benchmarking harness for the wildcard-matching
+// routines.
+//
+// Copyright 2025 Kirk J Krauss.  This is a Derivative Work based on
+// material that is copyright 2018 IBM Corporation and available at
+//
+//  http://developforperformance.com/MatchingWildcards_AnImprovedAlgorithmForBigData.html
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// This module replaces the single-number, interleaved timing that used to
+// accumulate into file-scope `static mut` variables in main.rs.  Each case
+// gets its own warmup, its own set of timed repetitions, and its own
+// min/max/mean/median/stddev, so that outliers and per-engine differences
+// aren't averaged away, and conversions that an engine wouldn't pay for in
+// production (CString round-trips, UTF-8 code point decoding) are done once
+// up front instead of inside the timed region.
+
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::time::Instant;
+
+use crate::fast_wild_compare;
+
+// One wild/tame pair to benchmark, grouped by input category (matching the
+// tame/wild/empty split used by the correctness testcases) so that the
+// O(n) and backtracking paths through fast_wild_compare show up separately.
+pub struct BenchCase
+{
+    pub category: &'static str,
+    pub wild: &'static str,
+    pub tame: &'static str,
+    pub expected: bool,
+}
+
+// The representative cases used for COMPARE_PERFORMANCE runs, spanning the
+// same categories as test_tame()/test_wild()/test_empty() at a few input
+// lengths apiece.
+pub const CASES: &[BenchCase] = &[
+    BenchCase { category: "empty", wild: "", tame: "", expected: true },
+    BenchCase { category: "empty", wild: "*", tame: "", expected: true },
+    BenchCase { category: "empty", wild: "?", tame: "", expected: false },
+    BenchCase { category: "tame", wild: "abc", tame: "abc", expected: true },
+    BenchCase { category: "tame", wild: "mississippi", tame: "mississippi",
+        expected: true },
+    BenchCase { category: "tame",
+        wild: "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        tame: "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa", expected: true },
+    BenchCase { category: "wild", wild: "*ccd", tame: "abcccd",
+        expected: true },
+    BenchCase { category: "wild", wild: "*issip*ss*",
+        tame: "mississipissippi", expected: true },
+    BenchCase { category: "wild", wild: "*a?b", tame: "caaab",
+        expected: true },
+    BenchCase { category: "wild",
+        wild: "abcabc?abc?abcabc?abc?abc?bc?abc?bc?bcd",
+        tame: "abcabcdabcdabcabcdabcdabcabcdabcabcabcd", expected: true },
+];
+
+// Timed repetitions per case, after warmup.  Large enough that the stddev
+// settles down on a typical dev machine without making a full benchmark
+// run noticeably slow.
+const WARMUP_REPS: u32 = 100;
+const TIMED_REPS: u32 = 2000;
+
+// Summary statistics for one set of timed samples, all in nanoseconds.
+pub struct SampleStats
+{
+    pub mean_ns: f64,
+    pub median_ns: f64,
+    pub min_ns: u128,
+    pub max_ns: u128,
+    pub stddev_ns: f64,
+}
+
+fn summarize(mut samples: Vec<u128>) -> SampleStats
+{
+    samples.sort_unstable();
+
+    let n = samples.len();
+    let min_ns = samples[0];
+    let max_ns = samples[n - 1];
+    let sum: u128 = samples.iter().sum();
+    let mean_ns = sum as f64 / n as f64;
+    let median_ns = if n % 2 == 0
+    {
+        (samples[n / 2 - 1] as f64 + samples[n / 2] as f64) / 2.0
+    }
+    else
+    {
+        samples[n / 2] as f64
+    };
+    let variance: f64 = samples.iter()
+        .map(|&sample|
+        {
+            let deviation = sample as f64 - mean_ns;
+            deviation * deviation
+        })
+        .sum::<f64>() / n as f64;
+
+    SampleStats
+    {
+        mean_ns,
+        median_ns,
+        min_ns,
+        max_ns,
+        stddev_ns: variance.sqrt(),
+    }
+}
+
+// Times `f` over `reps` repetitions, after `warmup` untimed repetitions
+// meant to settle caches and branch predictors.
+pub fn sample<F: FnMut()>(warmup: u32, reps: u32, mut f: F) -> SampleStats
+{
+    for _ in 0..warmup
+    {
+        f();
+    }
+
+    let mut samples_ns: Vec<u128> = Vec::with_capacity(reps as usize);
+
+    for _ in 0..reps
+    {
+        let timer = Instant::now();
+        f();
+        samples_ns.push(timer.elapsed().as_nanos());
+    }
+
+    summarize(samples_ns)
+}
+
+fn print_stats(label: &str, category: &str, length: usize, stats: &SampleStats)
+{
+    println!(
+        "  [{category:>5}, len {length:>4}] {label:<22} mean {:>9.0}ns \
+median {:>9.0}ns  min {:>7}ns  max {:>9}ns  stddev {:>9.0}ns",
+        stats.mean_ns, stats.median_ns, stats.min_ns, stats.max_ns,
+        stats.stddev_ns);
+}
+
+// Runs every case in `cases` through all four routines, reporting
+// per-case, per-routine throughput instead of one cumulative total.
+pub fn run_benchmarks(cases: &[BenchCase])
+{
+    println!(
+        "\nBenchmark results ({WARMUP_REPS} warmup + {TIMED_REPS} timed \
+reps per case):");
+
+    for case in cases
+    {
+        assert_eq!(case.expected,
+            fast_wild_compare::fast_wild_compare_ascii(case.wild, case.tame),
+            "fast_wild_compare_ascii result mismatch for case {:?}",
+            case.wild);
+
+        let wild_points = fast_wild_compare::utf8_code_points(case.wild);
+        let tame_points = fast_wild_compare::utf8_code_points(case.tame);
+
+        assert_eq!(case.expected,
+            fast_wild_compare::fast_wild_compare_utf8(&wild_points,
+                &tame_points),
+            "fast_wild_compare_utf8 result mismatch for case {:?}",
+            case.wild);
+
+        // Built once, outside the timed region, so the per-call cost of
+        // the C/C++ routines isn't polluted by the conversion cost.
+        let c_wild = CString::new(case.wild).expect("CString::new failed");
+        let c_tame = CString::new(case.tame).expect("CString::new failed");
+        let c_wild_ptr = c_wild.as_ptr() as *mut c_char;
+        let c_tame_ptr = c_tame.as_ptr() as *mut c_char;
+
+        unsafe
+        {
+            assert_eq!(case.expected, crate::FastWildCompare(c_wild_ptr,
+                c_tame_ptr), "FastWildCompare result mismatch for case {:?}",
+                case.wild);
+            assert_eq!(case.expected, crate::FastWildComparePortable(
+                c_wild_ptr, c_tame_ptr),
+                "FastWildComparePortable result mismatch for case {:?}",
+                case.wild);
+        }
+
+        let ascii_stats = sample(WARMUP_REPS, TIMED_REPS, ||
+        {
+            fast_wild_compare::fast_wild_compare_ascii(case.wild, case.tame);
+        });
+        let utf8_stats = sample(WARMUP_REPS, TIMED_REPS, ||
+        {
+            fast_wild_compare::fast_wild_compare_utf8(&wild_points,
+                &tame_points);
+        });
+        let fwc_cpp_stats = sample(WARMUP_REPS, TIMED_REPS, || unsafe
+        {
+            crate::FastWildCompare(c_wild_ptr, c_tame_ptr);
+        });
+        let fwcp_cpp_stats = sample(WARMUP_REPS, TIMED_REPS, || unsafe
+        {
+            crate::FastWildComparePortable(c_wild_ptr, c_tame_ptr);
+        });
+
+        print_stats("fast_wild_compare_ascii", case.category,
+            case.tame.len(), &ascii_stats);
+        print_stats("fast_wild_compare_utf8", case.category,
+            case.tame.len(), &utf8_stats);
+        print_stats("FastWildCompare", case.category, case.tame.len(),
+            &fwc_cpp_stats);
+        print_stats("FastWildComparePortable", case.category,
+            case.tame.len(), &fwcp_cpp_stats);
+    }
+}
@@ -1,444 +1,1895 @@
-// Rust routines for matching wildcards.
-//
-// Copyright 2025 Kirk J Krauss.  This is a Derivative Work based on 
-// material that is copyright 2018 IBM Corporation and available at
-//
-//  http://developforperformance.com/MatchingWildcards_AnImprovedAlgorithmForBigData.html
-// 
-// Licensed under the Apache License, Version 2.0 (the "License");
-// you may not use this file except in compliance with the License.
-// You may obtain a copy of the License at
-// 
-//     http://www.apache.org/licenses/LICENSE-2.0
-// 
-// Unless required by applicable law or agreed to in writing, software
-// distributed under the License is distributed on an "AS IS" BASIS,
-// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
-// See the License for the specific language governing permissions and
-// limitations under the License.
-
-// Rust implementation of FastWildCompare(), for ASCII text.
-//
-// Compares two ASCII &str's.  Accepts '?' as a single-character wildcard.
-// For each '*' wildcard, seeks out a matching sequence of any characters 
-// beyond it.  Otherwise compares the &str's a character at a time. 
-//
-pub fn fast_wild_compare_ascii(
-          wild_str: &str, 
-          tame_str: &str) -> bool
-{
-	let mut iwild: usize = 0;  // Index for both input &str's in upper loop
-	let mut itame: usize;      // Index for tame &str, used in lower loop
-	let mut iwild_sequence: usize; // Index for prospective match after '*'
-	let mut itame_sequence: usize; // Index for prospective match in tame &str
-
-    // Find a first wildcard, if one exists, and the beginning of any  
-    // prospectively matching sequence after it.
-    loop
-    {
-		// Check for the end from the start.  Get out fast, if possible.
-		if tame_str.len() <= iwild
-		{
-			if wild_str.len() > iwild
-			{
-				while wild_str.as_bytes()[iwild] == '*' as u8
-				{
-					iwild += 1;
-					
-					if wild_str.len() <= iwild
-					{
-						return true;       // "ab" matches "ab*".
-					}
-				}
-
-			    return false;              // "abcd" doesn't match "abc".
-			}
-			else
-			{
-				return true;               // "abc" matches "abc".
-			}
-		}
-		else if wild_str.len() <= iwild
-		{
-		    return false;                  // "abc" doesn't match "abcd".
-		}
-		else if wild_str.as_bytes()[iwild] == '*' as u8
-		{
-			// Got wild: set up for the second loop and skip on down there.
-			itame = iwild;
-
-			loop
-			{
-				iwild += 1;
-
-				if wild_str.len() <= iwild
-				{
-					return true;               // "abc*" matches "abcd".
-				}
-				
-				if wild_str.as_bytes()[iwild] == '*' as u8
-				{
-					continue;
-				}
-				
-				break;
-			}
-
-			// Search for the next prospective match.
-			if wild_str.as_bytes()[iwild] != '?' as u8
-			{
-				while wild_str.as_bytes()[iwild] != tame_str.as_bytes()[itame]
-				{
-					itame += 1;
-
-					if tame_str.len() <= itame
-					{
-						return false;      // "a*bc" doesn't match "ab".
-					}
-				}
-			}
-
-			// Keep fallback positions for retry in case of incomplete match.
-			iwild_sequence = iwild;
-			itame_sequence = itame;
-			break;
-		}
-		else if wild_str.as_bytes()[iwild] != tame_str.as_bytes()[iwild] && 
-				wild_str.as_bytes()[iwild] != '?' as u8
-		{
-			return false;                  // "abc" doesn't match "abd".
-		}
-
-		iwild += 1;                        // Everything's a match, so far.
-	}
-
-    // Find any further wildcards and any further matching sequences.
-    loop
-    {
-		if wild_str.len() > iwild && wild_str.as_bytes()[iwild] == '*' as u8
-        {
-            // Got wild again.
-			loop
-			{
-				iwild += 1;
-
-				if wild_str.len() <= iwild
-				{
-					return true;           // "ab*c*" matches "abcd".
-				}
-				
-				if wild_str.as_bytes()[iwild] != '*' as u8
-				{
-					break;
-				}
-			}
-
-			if tame_str.len() <= itame
-            {
-                return false;              // "*bcd*" doesn't match "abc".
-            }
-
-            // Search for the next prospective match.
-            if wild_str.as_bytes()[iwild] != '?' as u8
-            {
-                while tame_str.len() > itame && 
-				      wild_str.as_bytes()[iwild] != tame_str.as_bytes()[itame]
-                {
-					itame += 1;
-
-                    if tame_str.len() <= itame
-                    {
-                        return false;      // "a*b*c" doesn't match "ab".
-                    }
-                }
-            }
-
-            // Keep the new fallback positions.
-			iwild_sequence = iwild;
-			itame_sequence = itame;
-        }
-		else
-		{
-            // The equivalent portion of the upper loop is really simple.
-            if tame_str.len() <= itame
-            {
-				if wild_str.len() <= iwild
-				{
-					return true;           // "*b*c" matches "abc".
-				}
-			
-                return false;              // "*bcd" doesn't match "abc".
-            }
-			
-			if wild_str.len() <= iwild ||
-		       wild_str.as_bytes()[iwild] != tame_str.as_bytes()[itame] && 
-		       wild_str.as_bytes()[iwild] != '?' as u8
-			{
-				// A fine time for questions.
-				while wild_str.len() > iwild_sequence && 
-				      wild_str.as_bytes()[iwild_sequence] == '?' as u8
-				{
-					iwild_sequence += 1;
-					itame_sequence += 1;
-				}
-
-				iwild = iwild_sequence;
-
-				// Fall back, but never so far again.
-				loop
-				{
-					itame_sequence += 1;
-
-					if tame_str.len() <= itame_sequence
-					{
-						if wild_str.len() <= iwild
-						{
-							return true;   // "*a*b" matches "ab".
-						}
-						else
-						{
-							return false;  // "*a*b" doesn't match "ac".
-						}
-					}
-
-					if wild_str.len() > iwild && wild_str.as_bytes()[iwild] == 
-					   tame_str.as_bytes()[itame_sequence]
-					{
-						break;
-					}
-				}
-
-	            itame = itame_sequence;
-			}
-        }
-
-        // Another check for the end, at the end.
-        if tame_str.len() <= itame
-		{
-			if wild_str.len() <= iwild
-			{
-				return true;           // "*bc" matches "abc".
-			}
-
-			return false;              // "*bc" doesn't match "abcd".
-		}
-
-        iwild += 1;                    // Everything's still a match.
-        itame += 1;
-    }
-}
-
-
-// Rust implementation of FastWildCompare(), for UTF-8-encoded content.
-//
-// Accepts two Box'd slices of 32-bit code points, typically created from 
-// Strings, and compares their content.  Accepts '?' as a single-code-point 
-// wildcard.  For each '*' wildcard, seeks out a matching sequence of 
-// code points beyond it.  Otherwise compares the content a code point at 
-// a time.
-//
-pub fn fast_wild_compare_utf8(
-          wild_slice: Box<[char]>, 
-          tame_slice: Box<[char]>) -> bool
-{
-	let mut iwild: usize = 0;  // Index for both inputs in upper loop
-	let mut itame: usize;      // Index for tame content, used in lower loop
-	let mut iwild_sequence: usize; // Index for prospective match after '*'
-	let mut itame_sequence: usize; // Index for match in tame content
-
-    // Find a first wildcard, if one exists, and the beginning of any  
-    // prospectively matching sequence after it.
-    loop
-    {
-		// Check for the end from the start.  Get out fast, if possible.
-		if tame_slice.len() <= iwild
-		{
-			if wild_slice.len() > iwild
-			{
-				while wild_slice[iwild] == '*'
-				{
-					iwild += 1;
-					
-					if wild_slice.len() <= iwild
-					{
-						return true;       // "ab" matches "ab*".
-					}
-				}
-
-			    return false;              // "abcd" doesn't match "abc".
-			}
-			else
-			{
-				return true;               // "abc" matches "abc".
-			}
-		}
-		else if wild_slice.len() <= iwild
-		{
-		    return false;                  // "abc" doesn't match "abcd".
-		}		
-		else if wild_slice[iwild] == '*'
-		{
-			// Got wild: set up for the second loop and skip on down there.
-			itame = iwild;
-
-			loop
-			{
-				iwild += 1;
-				
-				if wild_slice.len() <= iwild
-				{
-					return true;           // "abc*" matches "abcd".
-				}
-
-				if wild_slice[iwild] == '*'
-				{
-					continue;
-				}
-				
-				break;
-			}
-
-			// Search for the next prospective match.
-			if wild_slice[iwild] != '?'
-			{
-				while wild_slice[iwild] != tame_slice[itame]
-				{
-					itame += 1;
-
-					if tame_slice.len() <= itame
-					{
-						return false;      // "a*bc" doesn't match "ab".
-					}
-				}
-			}
-
-			// Keep fallback positions for retry in case of incomplete match.
-			iwild_sequence = iwild;
-			itame_sequence = itame;
-			break;
-		}
-		else if wild_slice[iwild] != tame_slice[iwild] && 
-				wild_slice[iwild] != '?'
-		{
-			return false;                  // "abc" doesn't match "abd".
-		}
-
-		iwild += 1;                        // Everything's a match, so far.
-	}
-
-    // Find any further wildcards and any further matching sequences.
-    loop
-    {
-		if wild_slice.len() > iwild && wild_slice[iwild] == '*'
-        {
-            // Got wild again.
-			loop
-			{
-				iwild += 1;
-
-				if wild_slice.len() <= iwild
-				{
-					return true;           // "ab*c*" matches "abcd".
-				}
-				
-				if wild_slice[iwild] != '*'
-				{
-					break;
-				}
-			}
-
-			if tame_slice.len() <= itame
-            {
-                return false;              // "*bcd*" doesn't match "abc".
-            }
-
-            // Search for the next prospective match.
-            if wild_slice[iwild] != '?'
-            {
-                while tame_slice.len() > itame && 
-				      wild_slice[iwild] != tame_slice[itame]
-                {
-					itame += 1;
-
-                    if tame_slice.len() <= itame
-                    {
-                        return false;      // "a*b*c" doesn't match "ab".
-                    }
-                }
-            }
-
-            // Keep the new fallback positions.
-			iwild_sequence = iwild;
-			itame_sequence = itame;
-        }
-		else
-		{
-            // The equivalent portion of the upper loop is really simple.
-            if tame_slice.len() <= itame
-            {
-				if wild_slice.len() <= iwild
-				{
-					return true;           // "*b*c" matches "abc".
-				}
-			
-                return false;              // "*bcd" doesn't match "abc".
-            }
-			
-			if wild_slice.len() <= iwild ||
-		       wild_slice[iwild] != tame_slice[itame] && 
-		       wild_slice[iwild] != '?'
-			{
-				// A fine time for questions.
-				while wild_slice.len() > iwild_sequence && 
-				      wild_slice[iwild_sequence] == '?'
-				{
-					iwild_sequence += 1;
-					itame_sequence += 1;
-				}
-
-				iwild = iwild_sequence;
-
-				// Fall back, but never so far again.
-				loop
-				{
-					itame_sequence += 1;
-
-					if tame_slice.len() <= itame_sequence
-					{
-						if wild_slice.len() <= iwild
-						{
-							return true;   // "*a*b" matches "ab".
-						}
-						else
-						{
-							return false;  // "*a*b" doesn't match "ac".
-						}
-					}
-
-					if wild_slice.len() > iwild && 
-					    wild_slice[iwild] == tame_slice[itame_sequence]
-					{
-						break;
-					}
-				}
-
-				itame = itame_sequence;
-			}
-        }
-
-        // Another check for the end, at the end.
-        if tame_slice.len() <= itame
-		{
-			if wild_slice.len() <= iwild
-			{
-				return true;           // "*bc" matches "abc".
-			}
-
-			return false;              // "*bc" doesn't match "abcd".
-		}
-
-        iwild += 1;                    // Everything's still a match.
-        itame += 1;	
-    }
-}
-
+// Rust routines for matching wildcards.
+//
+// Copyright 2025 Kirk J Krauss.  This is a Derivative Work based on
+// material that is copyright 2018 IBM Corporation and available at
+//
+//  http://developforperformance.com/MatchingWildcards_AnImprovedAlgorithmForBigData.html
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Wild-string syntax recognized by every routine and type in this module:
+// literal characters compare as-is; '?' matches any single character;
+// '*' matches a run of zero or more characters; and `[...]` matches any
+// one of the listed characters, with `[a-z]` ranges and a leading `!`/`^`
+// negating the set, as detailed on `parse_ascii_bracket`/`parse_utf8_bracket`.
+// `Mode` flags below layer case-insensitivity, backslash escapes, and
+// path-aware globbing on top of that core syntax.
+
+// Flags controlling how a wild/tame comparison behaves, in the spirit of
+// gix-glob's `wildmatch::Mode` bitflags.  Combine flags with `|`; pass
+// `Mode::NONE` to get the historical, case-sensitive behavior.
+//
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Mode(u8);
+
+impl Mode
+{
+	// No special behavior: plain, case-sensitive matching.
+	pub const NONE: Mode = Mode(0);
+
+	// Compare '?', '[...]', and literal characters without regard to ASCII
+	// case, so that e.g. 'A' and 'a' are considered equal.
+	pub const CASE_INSENSITIVE: Mode = Mode(1 << 0);
+
+	// Treat a '\' in the wild string as an escape: the character following
+	// it is compared literally, even if it would otherwise be '*', '?', or
+	// the start of a `[...]` class.  A trailing '\' at the end of the wild
+	// string is treated as a literal backslash.  This escape is only
+	// recognized between atoms; once a `[...]` class has been entered, its
+	// members are parsed by `parse_ascii_bracket`/`parse_utf8_bracket`
+	// without any further escaping, so `]` must appear first in the class
+	// (or after a leading `!`/`^`) to be matched literally.
+	pub const BACKSLASH_ESCAPES: Mode = Mode(1 << 1);
+
+	// Treat '/' as a path separator: '?' and `[...]` never match it, and a
+	// single '*' cannot skip over it either, so it only matches within one
+	// path segment.  A run of two or more consecutive '*' characters
+	// (`**`) is exempt and may still match across '/', letting it stand in
+	// for "any number of path segments" as in gix-glob's
+	// `NO_MATCH_SLASH_LITERAL` mode.  Because the underlying algorithm
+	// retries only the most recently seen '*' on a failed match, a later
+	// single '*' that needs to reach past a '/' a still-earlier '**' left
+	// uncrossed won't trigger backtracking into that earlier '**'.
+	pub const NO_MATCH_SLASH_LITERAL: Mode = Mode(1 << 2);
+
+	// Like `CASE_INSENSITIVE`, but for the UTF-8 routine: folds each code
+	// point individually via Unicode case folding (approximated by taking
+	// the first code point `char::to_lowercase()`/`char::to_uppercase()`
+	// yields, rather than a full Unicode simple-case-fold table) instead
+	// of comparing ASCII letters only.  Folding one code point at a time,
+	// during the comparison itself, keeps the wild/tame slices aligned —
+	// unlike having the caller `to_lowercase()` whole strings beforehand,
+	// which can desynchronize them whenever a character's full lowercase
+	// form spans more than one code point (e.g. 'İ').  Has no effect on
+	// the ASCII routine.  Implies `CASE_INSENSITIVE`-like behavior for
+	// non-ASCII text in addition to it.
+	pub const UNICODE_CASE_FOLD: Mode = Mode(1 << 3);
+
+	// Returns whether every flag set in `other` is also set in `self`.
+	pub const fn contains(self, other: Mode) -> bool
+	{
+		self.0 & other.0 == other.0
+	}
+}
+
+impl std::ops::BitOr for Mode
+{
+	type Output = Mode;
+
+	fn bitor(self, rhs: Mode) -> Mode
+	{
+		Mode(self.0 | rhs.0)
+	}
+}
+
+impl std::ops::BitOrAssign for Mode
+{
+	fn bitor_assign(&mut self, rhs: Mode)
+	{
+		self.0 |= rhs.0;
+	}
+}
+
+impl Default for Mode
+{
+	fn default() -> Mode
+	{
+		Mode::NONE
+	}
+}
+
+// Compares two ASCII bytes for equality, folding A-Z/a-z together when
+// `mode` requests case-insensitive matching.
+//
+#[inline]
+fn ascii_eq(mode: Mode, a: u8, b: u8) -> bool
+{
+	if mode.contains(Mode::CASE_INSENSITIVE)
+	{
+		a.to_ascii_lowercase() == b.to_ascii_lowercase()
+	}
+	else
+	{
+		a == b
+	}
+}
+
+// Compares two code points for equality, folding case when `mode`
+// requests it.  `Mode::UNICODE_CASE_FOLD` folds every code point;
+// `Mode::CASE_INSENSITIVE` alone only folds ASCII letters, which keeps
+// this allocation-free and matches gix-glob's documented semantics.
+//
+#[inline]
+fn char_eq(mode: Mode, a: char, b: char) -> bool
+{
+	if mode.contains(Mode::UNICODE_CASE_FOLD)
+	{
+		a == b || case_fold_char(a) == case_fold_char(b)
+	}
+	else if mode.contains(Mode::CASE_INSENSITIVE)
+	{
+		a.eq_ignore_ascii_case(&b)
+	}
+	else
+	{
+		a == b
+	}
+}
+
+// Approximates Unicode simple case folding for one code point by taking
+// the first code point `char::to_lowercase()` yields.  Comparing one code
+// point at a time like this (rather than lowercasing whole strings ahead
+// of time) keeps wild/tame positions aligned even for code points whose
+// full lowercasing expands to more than one code point.
+//
+// `char::to_lowercase()` differs from Unicode simple case folding for a
+// handful of code points that lowercase to themselves but fold to another
+// letter.  Only Greek final sigma, 'ς' (U+03C2), is special-cased here: it
+// folds to medial sigma, 'σ' (U+03C3), the same as 'Σ'.  The others, such
+// as long s, 'ſ' (U+017F), are compared by their lowercase form alone, so
+// e.g. 'ſ' does not match 's'; there is no full fold table.
+#[inline]
+fn case_fold_char(c: char) -> char
+{
+	if c == '\u{03C2}'
+	{
+		return '\u{03C3}';
+	}
+
+	c.to_lowercase().next().unwrap_or(c)
+}
+
+// Parses a `[...]` bracket expression in `wild` starting at the opening
+// `[` found at `open`.  On success, returns the index of the matching `]`
+// together with whether the class is negated (`[!...]`/`[^...]`) and the
+// inclusive byte ranges that make up its members.  Returns `None` if no
+// closing `]` is found, in which case callers should treat the `[` as a
+// literal character.  This follows the same bracket-expression rules as
+// the globbing matcher in monotone's globish.cc: a `]` right after `[` or
+// `[!`/`[^` is a literal member rather than an empty, immediately-closed
+// class.
+//
+fn parse_ascii_bracket(wild: &[u8], open: usize) -> Option<(usize, bool, Vec<(u8, u8)>)>
+{
+	let mut i = open + 1;
+
+	if i >= wild.len()
+	{
+		return None;
+	}
+
+	let mut negate = false;
+
+	if wild[i] == b'!' || wild[i] == b'^'
+	{
+		negate = true;
+		i += 1;
+	}
+
+	let mut members: Vec<(u8, u8)> = Vec::new();
+	let mut first = true;
+
+	loop
+	{
+		if i >= wild.len()
+		{
+			return None;
+		}
+
+		if wild[i] == b']' && !first
+		{
+			return Some((i, negate, members));
+		}
+
+		first = false;
+
+		let lo = wild[i];
+		i += 1;
+
+		if i + 1 < wild.len() && wild[i] == b'-' && wild[i + 1] != b']'
+		{
+			members.push((lo, wild[i + 1]));
+			i += 2;
+		}
+		else
+		{
+			members.push((lo, lo));
+		}
+	}
+}
+
+// Tests whether `byte` is a member of an ASCII bracket class, honoring
+// `mode`'s case-insensitivity.
+//
+fn ascii_class_contains(mode: Mode, byte: u8, members: &[(u8, u8)]) -> bool
+{
+	if mode.contains(Mode::CASE_INSENSITIVE)
+	{
+		let lower = byte.to_ascii_lowercase();
+		let upper = byte.to_ascii_uppercase();
+
+		members.iter().any(|&(lo, hi)|
+			(lower >= lo && lower <= hi) || (upper >= lo && upper <= hi))
+	}
+	else
+	{
+		members.iter().any(|&(lo, hi)| byte >= lo && byte <= hi)
+	}
+}
+
+// Rust implementation of FastWildCompare(), for ASCII text.
+//
+// Compares two ASCII &str's.  Accepts '?' as a single-character wildcard
+// and `[...]`/`[a-z]`/`[!...]` as a single-character class, in addition
+// to `*`.  For each '*' wildcard, seeks out a matching sequence of any
+// characters beyond it.  Otherwise compares the &str's a character at a
+// time.
+//
+pub fn fast_wild_compare_ascii(
+          wild_str: &str,
+          tame_str: &str) -> bool
+{
+	fast_wild_compare_ascii_with(wild_str, tame_str, Mode::NONE)
+}
+
+// As `fast_wild_compare_ascii`, but accepts a `Mode` controlling optional
+// matching behaviors such as case-insensitive comparison.
+//
+// A thin wrapper around `Pattern`: compiles `wild_str` and matches once.
+// Reach for `Pattern::compile_with` directly when comparing many tame
+// strings against the same wild string, so the tokenizing only happens
+// once.
+//
+pub fn fast_wild_compare_ascii_with(
+          wild_str: &str,
+          tame_str: &str,
+          mode: Mode) -> bool
+{
+	Pattern::compile_with(wild_str, mode).matches(tame_str)
+}
+
+// On a successful match, returns the byte ranges (start..end into
+// `tame_str`) consumed by each '?' and run of '*' in `wild_str`, in
+// pattern order.  Returns `None` if `wild_str` doesn't match `tame_str`.
+// See `Pattern::captures` for how captures behave across `*` backtracking.
+pub fn fast_wild_capture(
+          wild_str: &str,
+          tame_str: &str) -> Option<Vec<std::ops::Range<usize>>>
+{
+	fast_wild_capture_with(wild_str, tame_str, Mode::NONE)
+}
+
+// As `fast_wild_capture`, but accepts a `Mode` controlling optional
+// matching behaviors such as case-insensitive comparison.
+pub fn fast_wild_capture_with(
+          wild_str: &str,
+          tame_str: &str,
+          mode: Mode) -> Option<Vec<std::ops::Range<usize>>>
+{
+	Pattern::compile_with(wild_str, mode).captures(tame_str)
+}
+
+// One piece of a compiled `Pattern`: either a run of literal bytes to be
+// matched atomically, a single-byte wildcard ('?' or a `[...]` class), or
+// a '*' standing in for a run of zero or more bytes.  Escapes are resolved
+// at compile time, so a `Token::Literal` may itself contain a byte that
+// was written as `\*` or `\?` in the original wild string.
+//
+#[derive(Clone, Debug)]
+enum Token
+{
+	Literal(Vec<u8>),
+	Any,
+	Class { negate: bool, members: Vec<(u8, u8)> },
+	Star { crosses_slash: bool },
+}
+
+// A wild pattern, pre-tokenized so that matching many tame strings against
+// it doesn't re-scan the original wild bytes each time.  Build one with
+// `Pattern::compile` (or `Pattern::compile_with` for a non-default `Mode`)
+// and reuse it across calls to `matches`, e.g. when filtering a batch of
+// log lines or filenames against the same pattern.
+//
+#[derive(Clone, Debug)]
+pub struct Pattern
+{
+	tokens: Vec<Token>,
+	mode: Mode,
+}
+
+impl Pattern
+{
+	// Compiles `wild` with `Mode::NONE`.
+	pub fn compile(wild: &str) -> Pattern
+	{
+		Pattern::compile_with(wild, Mode::NONE)
+	}
+
+	// As `compile`, but accepts a `Mode` controlling optional matching
+	// behaviors such as case-insensitive comparison.  `Mode::BACKSLASH_ESCAPES`
+	// only affects tokenizing: an escaped metacharacter is folded into the
+	// surrounding literal run once, here, rather than being re-examined on
+	// every call to `matches`.  `Mode::UNICODE_CASE_FOLD` has no effect
+	// here, as documented on the flag itself: this is the ASCII/byte
+	// engine, and only `Mode::CASE_INSENSITIVE`'s ASCII-letter folding
+	// applies to it.  Use the `_utf8` entry points for Unicode case
+	// folding.
+	pub fn compile_with(wild: &str, mode: Mode) -> Pattern
+	{
+		let wild = wild.as_bytes();
+		let mut tokens: Vec<Token> = Vec::new();
+		let mut literal: Vec<u8> = Vec::new();
+		let mut i = 0;
+
+		while i < wild.len()
+		{
+			if mode.contains(Mode::BACKSLASH_ESCAPES) && wild[i] == b'\\' && i + 1 < wild.len()
+			{
+				literal.push(wild[i + 1]);
+				i += 2;
+				continue;
+			}
+
+			if wild[i] == b'*'
+			{
+				if !literal.is_empty()
+				{
+					tokens.push(Token::Literal(std::mem::take(&mut literal)));
+				}
+
+				let mut star_count: u32 = 0;
+
+				while i < wild.len() && wild[i] == b'*'
+				{
+					star_count += 1;
+					i += 1;
+				}
+
+				let crosses_slash = star_count >= 2 || !mode.contains(Mode::NO_MATCH_SLASH_LITERAL);
+
+				tokens.push(Token::Star { crosses_slash });
+				continue;
+			}
+
+			if wild[i] == b'?'
+			{
+				if !literal.is_empty()
+				{
+					tokens.push(Token::Literal(std::mem::take(&mut literal)));
+				}
+
+				tokens.push(Token::Any);
+				i += 1;
+				continue;
+			}
+
+			if wild[i] == b'['
+			{
+				if let Some((end, negate, members)) = parse_ascii_bracket(wild, i)
+				{
+					if !literal.is_empty()
+					{
+						tokens.push(Token::Literal(std::mem::take(&mut literal)));
+					}
+
+					tokens.push(Token::Class { negate, members });
+					i = end + 1;
+					continue;
+				}
+
+				// An unterminated `[` falls back to a literal '['.
+			}
+
+			literal.push(wild[i]);
+			i += 1;
+		}
+
+		if !literal.is_empty()
+		{
+			tokens.push(Token::Literal(literal));
+		}
+
+		Pattern { tokens, mode }
+	}
+
+	// Matches `tame_str` against the pre-built token vector, falling back
+	// to the most recent '*' on a failed match, so the original wild bytes
+	// aren't re-parsed per call.  `fast_wild_compare_ascii_with` is a thin
+	// wrapper around this.
+	pub fn matches(&self, tame_str: &str) -> bool
+	{
+		let tokens = &self.tokens[..];
+		let tame = tame_str.as_bytes();
+		let mode = self.mode;
+
+		let mut itoken: usize = 0;
+		let mut itame: usize = 0;
+
+		// The most recently seen '*': the token index just past it, the
+		// tame position last tried there, and whether it may cross a '/'.
+		let mut backtrack: Option<(usize, usize, bool)> = None;
+
+		loop
+		{
+			if itoken < tokens.len()
+			{
+				if let Token::Star { crosses_slash } = tokens[itoken]
+				{
+					itoken += 1;
+					backtrack = Some((itoken, itame, crosses_slash));
+					continue;
+				}
+
+				if let Some(consumed) = token_try_match_ascii(tokens, itoken, tame, itame, mode)
+				{
+					itoken += 1;
+					itame += consumed;
+					continue;
+				}
+			}
+			else if itame >= tame.len()
+			{
+				return true;
+			}
+
+			// No direct match (or wild ran out first): fall back to the
+			// most recent '*', advancing one tame byte past where it was
+			// last tried, but never crossing a '/' a lone '*' must stop at.
+			match backtrack
+			{
+				Some((bt_itoken, bt_itame, crosses_slash)) =>
+				{
+					if bt_itame >= tame.len()
+					{
+						return false;
+					}
+
+					if !crosses_slash && tame[bt_itame] == b'/'
+					{
+						return false; // A lone '*' may not skip a '/'.
+					}
+
+					let next_itame = bt_itame + 1;
+
+					backtrack = Some((bt_itoken, next_itame, crosses_slash));
+					itoken = bt_itoken;
+					itame = next_itame;
+				}
+				None => return false,
+			}
+		}
+	}
+
+	// As `matches`, but on success also returns the byte range of
+	// `tame_str` consumed by each '?' and run of '*', in pattern order.
+	// `[...]` classes aren't captured, matching the crate's existing
+	// distinction between a class and a true wildcard atom.
+	//
+	// Keyed by each captured token's own index so that, when the single
+	// bookmark above backtracks and re-walks the tokens following it, a
+	// stale attempt is simply overwritten by the next one; only the
+	// ranges live at the moment of a successful match survive into the
+	// returned `BTreeMap`, whose ascending keys are exactly pattern order.
+	pub fn captures(&self, tame_str: &str) -> Option<Vec<std::ops::Range<usize>>>
+	{
+		let tokens = &self.tokens[..];
+		let tame = tame_str.as_bytes();
+		let mode = self.mode;
+
+		let mut itoken: usize = 0;
+		let mut itame: usize = 0;
+		let mut backtrack: Option<(usize, usize, bool)> = None;
+
+		// The currently active '*' run: its own token index, and the tame
+		// position where it began.  Finalized (and cleared) as soon as the
+		// token right after it succeeds, or at a successful end of match.
+		let mut star: Option<(usize, usize)> = None;
+
+		// The most recent '*' run as it was entered, put back into `star`
+		// whenever the bookmark backtracks so its range is recorded anew.
+		let mut last_star: Option<(usize, usize)> = None;
+		let mut captured: std::collections::BTreeMap<usize, (usize, usize)> = std::collections::BTreeMap::new();
+
+		loop
+		{
+			if itoken < tokens.len()
+			{
+				if let Token::Star { crosses_slash } = tokens[itoken]
+				{
+					star = Some((itoken, itame));
+					last_star = star;
+					itoken += 1;
+					backtrack = Some((itoken, itame, crosses_slash));
+					continue;
+				}
+
+				if let Some(consumed) = token_try_match_ascii(tokens, itoken, tame, itame, mode)
+				{
+					if let Some((star_itoken, start)) = star.take()
+					{
+						captured.insert(star_itoken, (start, itame));
+					}
+
+					if matches!(tokens[itoken], Token::Any)
+					{
+						captured.insert(itoken, (itame, itame + consumed));
+					}
+
+					itoken += 1;
+					itame += consumed;
+					continue;
+				}
+			}
+			else if itame >= tame.len()
+			{
+				if let Some((star_itoken, start)) = star.take()
+				{
+					captured.insert(star_itoken, (start, itame));
+				}
+
+				return Some(captured.into_values().map(|(start, end)| start..end).collect());
+			}
+
+			match backtrack
+			{
+				Some((bt_itoken, bt_itame, crosses_slash)) =>
+				{
+					if bt_itame >= tame.len()
+					{
+						return None;
+					}
+
+					if !crosses_slash && tame[bt_itame] == b'/'
+					{
+						return None;
+					}
+
+					let next_itame = bt_itame + 1;
+
+					backtrack = Some((bt_itoken, next_itame, crosses_slash));
+					itoken = bt_itoken;
+					itame = next_itame;
+					star = last_star;
+				}
+				None => return None,
+			}
+		}
+	}
+}
+
+// Tests the token at `itoken` (assumed not to be a `Token::Star`) against
+// `tame` starting at `itame`, returning the number of tame bytes it
+// consumed on success.
+//
+fn token_try_match_ascii(tokens: &[Token], itoken: usize, tame: &[u8], itame: usize, mode: Mode) -> Option<usize>
+{
+	match &tokens[itoken]
+	{
+		Token::Literal(bytes) =>
+		{
+			if itame + bytes.len() > tame.len()
+			{
+				return None;
+			}
+
+			for (k, &b) in bytes.iter().enumerate()
+			{
+				if !ascii_eq(mode, b, tame[itame + k])
+				{
+					return None;
+				}
+			}
+
+			Some(bytes.len())
+		}
+		Token::Any =>
+		{
+			if itame >= tame.len() || (mode.contains(Mode::NO_MATCH_SLASH_LITERAL) && tame[itame] == b'/')
+			{
+				return None;
+			}
+
+			Some(1)
+		}
+		Token::Class { negate, members } =>
+		{
+			if itame >= tame.len() || (mode.contains(Mode::NO_MATCH_SLASH_LITERAL) && tame[itame] == b'/')
+			{
+				return None;
+			}
+
+			if ascii_class_contains(mode, tame[itame], members) != *negate
+			{
+				Some(1)
+			}
+			else
+			{
+				None
+			}
+		}
+		Token::Star { .. } => unreachable!("'*' tokens are handled by the caller"),
+	}
+}
+
+// Parses a `[...]` bracket expression in `wild` starting at the opening
+// `[` found at `open`.  On success, returns the index of the matching `]`
+// together with whether the class is negated and the inclusive code-point
+// ranges that make up its members.  Returns `None` if no closing `]` is
+// found, in which case callers should treat the `[` as a literal
+// character.  Mirrors `parse_ascii_bracket`'s rules one code point at a
+// time, so range endpoints and members are full code points rather than
+// individual UTF-8 bytes.
+//
+fn parse_utf8_bracket(wild: &[char], open: usize) -> Option<(usize, bool, Vec<(char, char)>)>
+{
+	let mut i = open + 1;
+
+	if i >= wild.len()
+	{
+		return None;
+	}
+
+	let mut negate = false;
+
+	if wild[i] == '!' || wild[i] == '^'
+	{
+		negate = true;
+		i += 1;
+	}
+
+	let mut members: Vec<(char, char)> = Vec::new();
+	let mut first = true;
+
+	loop
+	{
+		if i >= wild.len()
+		{
+			return None;
+		}
+
+		if wild[i] == ']' && !first
+		{
+			return Some((i, negate, members));
+		}
+
+		first = false;
+
+		let lo = wild[i];
+		i += 1;
+
+		if i + 1 < wild.len() && wild[i] == '-' && wild[i + 1] != ']'
+		{
+			members.push((lo, wild[i + 1]));
+			i += 2;
+		}
+		else
+		{
+			members.push((lo, lo));
+		}
+	}
+}
+
+// Tests whether `ch` is a member of a UTF-8 bracket class, honoring
+// `mode`'s case-insensitivity: ASCII-only under `Mode::CASE_INSENSITIVE`,
+// every code point under `Mode::UNICODE_CASE_FOLD`.
+//
+fn utf8_class_contains(mode: Mode, ch: char, members: &[(char, char)]) -> bool
+{
+	if mode.contains(Mode::UNICODE_CASE_FOLD)
+	{
+		let lower = case_fold_char(ch);
+		let upper = ch.to_uppercase().next().unwrap_or(ch);
+
+		members.iter().any(|&(lo, hi)|
+			(ch >= lo && ch <= hi) || (lower >= lo && lower <= hi) || (upper >= lo && upper <= hi))
+	}
+	else if mode.contains(Mode::CASE_INSENSITIVE)
+	{
+		let lower = ch.to_ascii_lowercase();
+		let upper = ch.to_ascii_uppercase();
+
+		members.iter().any(|&(lo, hi)|
+			(lower >= lo && lower <= hi) || (upper >= lo && upper <= hi))
+	}
+	else
+	{
+		members.iter().any(|&(lo, hi)| ch >= lo && ch <= hi)
+	}
+}
+
+// Returns the number of code points spanned by the wild atom (literal,
+// '?', a `[...]` class, or an escaped literal) starting at `i`.  Assumes
+// the atom at `i` is not '*'.
+//
+fn atom_len_utf8(mode: Mode, wild: &[char], i: usize) -> usize
+{
+	if mode.contains(Mode::BACKSLASH_ESCAPES) && wild[i] == '\\' && i + 1 < wild.len()
+	{
+		return 2;
+	}
+
+	if wild[i] == '['
+	{
+		if let Some((end, _, _)) = parse_utf8_bracket(wild, i)
+		{
+			return end - i + 1;
+		}
+	}
+
+	1
+}
+
+// Tests the wild atom (literal, '?', `[...]` class, or escaped literal)
+// starting at `i` against a single tame code point, returning whether it
+// matches.  An unterminated `[` falls back to a literal `[` comparison.
+//
+fn match_atom_utf8(mode: Mode, wild: &[char], i: usize, tame_char: char) -> bool
+{
+	if mode.contains(Mode::BACKSLASH_ESCAPES) && wild[i] == '\\' && i + 1 < wild.len()
+	{
+		return char_eq(mode, wild[i + 1], tame_char);
+	}
+
+	if wild[i] == '?'
+	{
+		return !(mode.contains(Mode::NO_MATCH_SLASH_LITERAL) && tame_char == '/');
+	}
+
+	if wild[i] == '['
+	{
+		if let Some((_, negate, members)) = parse_utf8_bracket(wild, i)
+		{
+			if mode.contains(Mode::NO_MATCH_SLASH_LITERAL) && tame_char == '/'
+			{
+				return false;
+			}
+
+			return utf8_class_contains(mode, tame_char, &members) != negate;
+		}
+	}
+
+	char_eq(mode, wild[i], tame_char)
+}
+
+// Collects `s` into a `Vec<char>` of its 32-bit UTF-8 code points, suitable
+// for passing to `fast_wild_compare_utf8`/`fast_wild_compare_utf8_with`.
+// Keep the returned buffer around and reuse it across multiple comparisons
+// (e.g. as the tame side of many wild patterns) to avoid re-decoding the
+// same &str over and over.
+//
+pub fn utf8_code_points(s: &str) -> Vec<char>
+{
+	s.chars().collect()
+}
+
+// Rust implementation of FastWildCompare(), for UTF-8-encoded content.
+//
+// Accepts two slices of 32-bit code points, typically produced by
+// `utf8_code_points`, and compares their content.  Accepts '?' as a
+// single-code-point wildcard and `[...]`/`[a-z]`/`[!...]` as a
+// single-code-point class, in addition to `*`.  For each '*' wildcard,
+// seeks out a matching sequence of code points beyond it.  Otherwise
+// compares the content a code point at a time.
+//
+pub fn fast_wild_compare_utf8(
+          wild_slice: &[char],
+          tame_slice: &[char]) -> bool
+{
+	fast_wild_compare_utf8_with(wild_slice, tame_slice, Mode::NONE)
+}
+
+// As `fast_wild_compare_utf8`, but accepts a `Mode` controlling optional
+// matching behaviors such as case-insensitive comparison.  With plain
+// `Mode::CASE_INSENSITIVE`, folding is ASCII-only, which keeps this
+// allocation-free; non-ASCII code points are compared exactly.  Add
+// `Mode::UNICODE_CASE_FOLD` to fold non-ASCII code points as well.
+//
+pub fn fast_wild_compare_utf8_with(
+          wild: &[char],
+          tame: &[char],
+          mode: Mode) -> bool
+{
+
+	let mut iwild: usize = 0;  // Index for the wild content in both loops
+	let mut itame: usize = 0;  // Index for the tame content in both loops
+	let mut iwild_sequence: usize; // Index for prospective match after '*'
+	let mut itame_sequence: usize; // Index for match in tame content
+
+	// Whether the most recently seen run of '*' may match across a '/'.
+	// Only a run of two or more ("**") is allowed to under
+	// `Mode::NO_MATCH_SLASH_LITERAL`; a lone '*' stops at the separator.
+	let mut star_crosses_slash: bool;
+
+    // Find a first wildcard, if one exists, and the beginning of any
+    // prospectively matching sequence after it.
+    loop
+    {
+		// Check for the end from the start.  Get out fast, if possible.
+		if tame.len() <= itame
+		{
+			if wild.len() > iwild
+			{
+				while wild[iwild] == '*'
+				{
+					iwild += 1;
+
+					if wild.len() <= iwild
+					{
+						return true;       // "ab" matches "ab*".
+					}
+				}
+
+			    return false;              // "abcd" doesn't match "abc".
+			}
+			else
+			{
+				return true;               // "abc" matches "abc".
+			}
+		}
+		else if wild.len() <= iwild
+		{
+		    return false;                  // "abc" doesn't match "abcd".
+		}
+		else if wild[iwild] == '*'
+		{
+			// Got wild: set up for the second loop and skip on down there.
+			let mut star_count: u32 = 0;
+
+			loop
+			{
+				iwild += 1;
+				star_count += 1;
+
+				if wild.len() <= iwild
+				{
+					// A lone trailing '*' may not reach past a '/'.
+					return star_count >= 2 ||
+					       !mode.contains(Mode::NO_MATCH_SLASH_LITERAL) ||
+					       !tame[itame..].contains(&'/');
+				}
+
+				if wild[iwild] == '*'
+				{
+					continue;
+				}
+
+				break;
+			}
+
+			star_crosses_slash = star_count >= 2 || !mode.contains(Mode::NO_MATCH_SLASH_LITERAL);
+
+			// Search for the next prospective match.
+			if wild[iwild] != '?'
+			{
+				while !match_atom_utf8(mode, wild, iwild, tame[itame])
+				{
+					if !star_crosses_slash && tame[itame] == '/'
+					{
+						return false;  // A lone '*' may not skip a '/'.
+					}
+
+					itame += 1;
+
+					if tame.len() <= itame
+					{
+						return false;      // "a*bc" doesn't match "ab".
+					}
+				}
+			}
+			else if !star_crosses_slash && tame[itame] == '/'
+			{
+				return false;          // '?' right after '*' hit a '/'.
+			}
+
+			// Keep fallback positions for retry in case of incomplete match.
+			iwild_sequence = iwild;
+			itame_sequence = itame;
+			break;
+		}
+		else if !match_atom_utf8(mode, wild, iwild, tame[itame])
+		{
+			return false;                  // "abc" doesn't match "abd".
+		}
+		else
+		{
+			let alen = atom_len_utf8(mode, wild, iwild);
+			iwild += alen;
+			itame += 1;
+			continue;
+		}
+	}
+
+    // Find any further wildcards and any further matching sequences.
+    loop
+    {
+		if wild.len() > iwild && wild[iwild] == '*'
+        {
+            // Got wild again.
+			let mut star_count: u32 = 0;
+
+			loop
+			{
+				iwild += 1;
+				star_count += 1;
+
+				if wild.len() <= iwild
+				{
+					// A lone trailing '*' may not reach past a '/'.
+					return star_count >= 2 ||
+					       !mode.contains(Mode::NO_MATCH_SLASH_LITERAL) ||
+					       !tame[itame..].contains(&'/');
+				}
+
+				if wild[iwild] != '*'
+				{
+					break;
+				}
+			}
+
+			star_crosses_slash = star_count >= 2 || !mode.contains(Mode::NO_MATCH_SLASH_LITERAL);
+
+			if tame.len() <= itame
+            {
+                return false;              // "*bcd*" doesn't match "abc".
+            }
+
+            // Search for the next prospective match.
+            if wild[iwild] != '?'
+            {
+                while tame.len() > itame &&
+				      !match_atom_utf8(mode, wild, iwild, tame[itame])
+                {
+					if !star_crosses_slash && tame[itame] == '/'
+					{
+						return false;  // A lone '*' may not skip a '/'.
+					}
+
+					itame += 1;
+
+                    if tame.len() <= itame
+                    {
+                        return false;      // "a*b*c" doesn't match "ab".
+                    }
+                }
+            }
+            else if !star_crosses_slash && tame.len() > itame && tame[itame] == '/'
+            {
+                return false;              // '?' right after '*' hit a '/'.
+            }
+
+            // Keep the new fallback positions.
+			iwild_sequence = iwild;
+			itame_sequence = itame;
+        }
+		else
+		{
+            // The equivalent portion of the upper loop is really simple.
+            if tame.len() <= itame
+            {
+				if wild.len() <= iwild
+				{
+					return true;           // "*b*c" matches "abc".
+				}
+
+                return false;              // "*bcd" doesn't match "abc".
+            }
+
+			if wild.len() <= iwild || !match_atom_utf8(mode, wild, iwild, tame[itame])
+			{
+				// A fine time for questions.
+				while wild.len() > iwild_sequence && wild[iwild_sequence] == '?'
+				{
+					iwild_sequence += 1;
+					itame_sequence += 1;
+				}
+
+				iwild = iwild_sequence;
+
+				// Fall back, but never so far again.
+				loop
+				{
+					if itame_sequence >= tame.len()
+					{
+						return false;  // The '?' run above already ran past the end.
+					}
+
+					if !star_crosses_slash && tame[itame_sequence] == '/'
+					{
+						return false;  // A lone '*' may not skip a '/'.
+					}
+
+					itame_sequence += 1;
+
+					if tame.len() <= itame_sequence
+					{
+						if wild.len() <= iwild
+						{
+							return true;   // "*a*b" matches "ab".
+						}
+						else
+						{
+							return false;  // "*a*b" doesn't match "ac".
+						}
+					}
+
+					if wild.len() > iwild && match_atom_utf8(mode, wild, iwild, tame[itame_sequence])
+					{
+						break;
+					}
+				}
+
+				itame = itame_sequence;
+			}
+        }
+
+        // Another check for the end, at the end.
+        if tame.len() <= itame
+		{
+			if wild.len() <= iwild
+			{
+				return true;           // "*bc" matches "abc".
+			}
+
+			return false;              // "*bc" doesn't match "abcd".
+		}
+
+        iwild += atom_len_utf8(mode, wild, iwild); // Everything's still a match.
+        itame += 1;
+    }
+}
+
+// On a successful match, returns the code-point-aligned byte ranges
+// (start..end into `tame_str`) consumed by each '?' and run of '*' in
+// `wild`, in pattern order, so callers can slice `&str` directly.  `[...]`
+// classes aren't captured, matching `Pattern::captures`'s treatment of a
+// class as distinct from a true wildcard atom.  Returns `None` if `wild`
+// doesn't match `tame_str`.
+pub fn fast_wild_capture_utf8(
+          wild: &[char],
+          tame_str: &str) -> Option<Vec<std::ops::Range<usize>>>
+{
+	fast_wild_capture_utf8_with(wild, tame_str, Mode::NONE)
+}
+
+// As `fast_wild_capture_utf8`, but accepts a `Mode` controlling optional
+// matching behaviors such as case-insensitive comparison.
+pub fn fast_wild_capture_utf8_with(
+          wild: &[char],
+          tame_str: &str,
+          mode: Mode) -> Option<Vec<std::ops::Range<usize>>>
+{
+	let tame = utf8_code_points(tame_str);
+
+	// byte_offset[k] is the byte offset of tame[k] in tame_str; the
+	// trailing entry equals tame_str.len(), so a code-point range
+	// tame_start..tame_end maps straight to byte_offset[tame_start]..
+	// byte_offset[tame_end].
+	let mut byte_offset: Vec<usize> = Vec::with_capacity(tame.len() + 1);
+	let mut running = 0usize;
+
+	for &ch in &tame
+	{
+		byte_offset.push(running);
+		running += ch.len_utf8();
+	}
+
+	byte_offset.push(running);
+
+	let captures = capture_utf8_code_points(wild, &tame, mode)?;
+
+	Some(captures.into_iter()
+		.map(|(start, end)| byte_offset[start]..byte_offset[end])
+		.collect())
+}
+
+// The single-bookmark backtracking loop behind `fast_wild_capture_utf8_with`,
+// in code-point indices.  Mirrors `Pattern::matches`'s loop structure, but
+// reads wild atoms on the fly via `atom_len_utf8`/`match_atom_utf8` instead
+// of a pre-tokenized list (there being no UTF-8 equivalent of `Pattern`
+// yet), and records the code-point range consumed by each '?' and run of
+// '*', keyed by the wild index the atom starts at so a stale attempt left
+// behind by backtracking is simply overwritten by the next one.
+fn capture_utf8_code_points(wild: &[char], tame: &[char], mode: Mode)
+    -> Option<Vec<(usize, usize)>>
+{
+	let mut iwild: usize = 0;
+	let mut itame: usize = 0;
+	let mut backtrack: Option<(usize, usize, bool)> = None;
+
+	// The currently active '*' run: its own wild index, and the tame
+	// position where it began.  Finalized (and cleared) as soon as the
+	// atom right after it succeeds, or at a successful end of match.
+	let mut star: Option<(usize, usize)> = None;
+
+	// The most recent '*' run as it was entered, put back into `star`
+	// whenever the bookmark backtracks so its range is recorded anew.
+	let mut last_star: Option<(usize, usize)> = None;
+	let mut captured: std::collections::BTreeMap<usize, (usize, usize)> = std::collections::BTreeMap::new();
+
+	loop
+	{
+		if iwild < wild.len()
+		{
+			if wild[iwild] == '*'
+			{
+				let star_iwild = iwild;
+				let mut star_count: u32 = 0;
+
+				while iwild < wild.len() && wild[iwild] == '*'
+				{
+					iwild += 1;
+					star_count += 1;
+				}
+
+				let crosses_slash = star_count >= 2 ||
+					!mode.contains(Mode::NO_MATCH_SLASH_LITERAL);
+
+				star = Some((star_iwild, itame));
+				last_star = star;
+				backtrack = Some((iwild, itame, crosses_slash));
+				continue;
+			}
+
+			if itame < tame.len() && match_atom_utf8(mode, wild, iwild, tame[itame])
+			{
+				if let Some((star_iwild, start)) = star.take()
+				{
+					captured.insert(star_iwild, (start, itame));
+				}
+
+				if wild[iwild] == '?'
+				{
+					captured.insert(iwild, (itame, itame + 1));
+				}
+
+				iwild += atom_len_utf8(mode, wild, iwild);
+				itame += 1;
+				continue;
+			}
+		}
+		else if itame >= tame.len()
+		{
+			if let Some((star_iwild, start)) = star.take()
+			{
+				captured.insert(star_iwild, (start, itame));
+			}
+
+			return Some(captured.into_values().collect());
+		}
+
+		match backtrack
+		{
+			Some((bt_iwild, bt_itame, crosses_slash)) =>
+			{
+				if bt_itame >= tame.len()
+				{
+					return None;
+				}
+
+				if !crosses_slash && tame[bt_itame] == '/'
+				{
+					return None;
+				}
+
+				let next_itame = bt_itame + 1;
+
+				backtrack = Some((bt_iwild, next_itame, crosses_slash));
+				iwild = bt_iwild;
+				itame = next_itame;
+				star = last_star;
+			}
+			None => return None,
+		}
+	}
+}
+
+// One node of the Aho-Corasick trie built over every compiled pattern's
+// required literal fragments.  `outputs` is the set of literal indices
+// (into `WildcardSet::literals`) ending at this node, already widened to
+// include everything reachable via `fail` links so a scan only needs one
+// lookup per byte.
+//
+#[derive(Debug)]
+struct AcNode
+{
+	children: std::collections::HashMap<u8, usize>,
+	fail: usize,
+	outputs: Vec<usize>,
+}
+
+impl AcNode
+{
+	fn new() -> AcNode
+	{
+		AcNode { children: std::collections::HashMap::new(), fail: 0, outputs: Vec::new() }
+	}
+}
+
+// Builds an Aho-Corasick trie over `literals` and returns its nodes, with
+// the root at index 0.
+//
+fn build_ac_trie(literals: &[Vec<u8>]) -> Vec<AcNode>
+{
+	let mut nodes = vec![AcNode::new()];
+
+	for (id, literal) in literals.iter().enumerate()
+	{
+		let mut state = 0usize;
+
+		for &b in literal
+		{
+			state = match nodes[state].children.get(&b)
+			{
+				Some(&next) => next,
+				None =>
+				{
+					let next = nodes.len();
+					nodes.push(AcNode::new());
+					nodes[state].children.insert(b, next);
+					next
+				}
+			};
+		}
+
+		nodes[state].outputs.push(id);
+	}
+
+	let mut queue: std::collections::VecDeque<usize> = std::collections::VecDeque::new();
+
+	for &child in nodes[0].children.clone().values()
+	{
+		nodes[child].fail = 0;
+		queue.push_back(child);
+	}
+
+	while let Some(state) = queue.pop_front()
+	{
+		let children: Vec<(u8, usize)> = nodes[state].children.iter().map(|(&b, &c)| (b, c)).collect();
+
+		for (b, child) in children
+		{
+			let mut fail = nodes[state].fail;
+
+			while fail != 0 && !nodes[fail].children.contains_key(&b)
+			{
+				fail = nodes[fail].fail;
+			}
+
+			let child_fail = match nodes[fail].children.get(&b)
+			{
+				Some(&f) if f != child => f,
+				_ => 0,
+			};
+
+			nodes[child].fail = child_fail;
+
+			let inherited = nodes[child_fail].outputs.clone();
+			nodes[child].outputs.extend(inherited);
+
+			queue.push_back(child);
+		}
+	}
+
+	nodes
+}
+
+// Compiles a collection of wildcard patterns once, then answers "which of
+// these patterns match this tame string?" far faster than looping
+// `fast_wild_compare_ascii` over each one individually.
+//
+// Borrows the Aho-Corasick idea: every compiled pattern's literal runs
+// (the same `Token::Literal` fragments `Pattern` already tokenizes out,
+// i.e. the mandatory pieces between `*`s) are inserted into a single
+// automaton built once at construction.  A query runs the automaton
+// across the tame string in one pass to discover which literal fragments
+// are present; a pattern can only match if all of its required fragments
+// occur, in left-to-right order, at non-overlapping positions (and, for a
+// pattern that begins/ends with a literal fragment rather than a wildcard
+// or class, that first/last fragment must additionally land at the very
+// start/end of the tame string).  Only the candidates that survive this prefilter are confirmed
+// with the same `fast_wild_compare` core used elsewhere in this crate, so
+// a query costs roughly one linear automaton pass plus verification of
+// the few survivors rather than a full rescan per pattern.
+//
+// A pattern with no literal fragment at all (e.g. "*" or "?") can't be
+// pruned this way and is always passed through to confirmation.
+//
+pub struct WildcardSet
+{
+	patterns: Vec<Pattern>,
+	required: Vec<Vec<usize>>, // Per pattern, indices into `literals`, in order.
+	anchored_start: Vec<bool>,
+	anchored_end: Vec<bool>,
+
+	// Distinct literal fragments, deduplicated, and ASCII-lowercased up
+	// front under `Mode::CASE_INSENSITIVE` so the automaton can do plain
+	// byte comparisons; `case_insensitive` then folds the tame string the
+	// same way while scanning.  Final confirmation still runs the
+	// case-aware `Pattern::matches`, so this folding only has to be a
+	// sound prefilter, not the source of truth.
+	literals: Vec<Vec<u8>>,
+	nodes: Vec<AcNode>,     // The Aho-Corasick trie; root is index 0.
+	case_insensitive: bool,
+}
+
+impl WildcardSet
+{
+	// Compiles `wild_strs` with `Mode::NONE`.
+	pub fn compile(wild_strs: &[&str]) -> WildcardSet
+	{
+		WildcardSet::compile_with(wild_strs, Mode::NONE)
+	}
+
+	// As `compile`, but accepts a `Mode` shared by every pattern in the
+	// set.  Every pattern is confirmed through `Pattern::matches`, so
+	// `Mode::UNICODE_CASE_FOLD` is a no-op here the same way it is for
+	// `Pattern::compile_with` — it only takes effect on the `_utf8`
+	// entry points.
+	pub fn compile_with(wild_strs: &[&str], mode: Mode) -> WildcardSet
+	{
+		let patterns: Vec<Pattern> = wild_strs.iter().map(|w| Pattern::compile_with(w, mode)).collect();
+
+		let mut literal_ids: std::collections::HashMap<Vec<u8>, usize> = std::collections::HashMap::new();
+		let mut literals: Vec<Vec<u8>> = Vec::new();
+		let mut required: Vec<Vec<usize>> = Vec::new();
+		let mut anchored_start: Vec<bool> = Vec::new();
+		let mut anchored_end: Vec<bool> = Vec::new();
+
+		let case_insensitive = mode.contains(Mode::CASE_INSENSITIVE);
+
+		for pattern in &patterns
+		{
+			let tokens = &pattern.tokens;
+			let mut req: Vec<usize> = Vec::new();
+
+			for token in tokens
+			{
+				if let Token::Literal(bytes) = token
+				{
+					let key: Vec<u8> = if case_insensitive
+					{
+						bytes.iter().map(u8::to_ascii_lowercase).collect()
+					}
+					else
+					{
+						bytes.clone()
+					};
+
+					let id = match literal_ids.get(&key)
+					{
+						Some(&id) => id,
+						None =>
+						{
+							let id = literals.len();
+							literals.push(key.clone());
+							literal_ids.insert(key, id);
+							id
+						}
+					};
+
+					req.push(id);
+				}
+			}
+
+			anchored_start.push(matches!(tokens.first(), Some(Token::Literal(_))));
+			anchored_end.push(matches!(tokens.last(), Some(Token::Literal(_))));
+			required.push(req);
+		}
+
+		let nodes = build_ac_trie(&literals);
+
+		WildcardSet { patterns, required, anchored_start, anchored_end, literals, nodes, case_insensitive }
+	}
+
+	// Returns the indices (in compile-time order) of every pattern that
+	// matches `tame_str`.
+	pub fn matches(&self, tame_str: &str) -> Vec<usize>
+	{
+		let tame = tame_str.as_bytes();
+
+		// occurrences[literal_id] holds every (start, end) span at which
+		// that literal fragment was found, in left-to-right order.
+		let mut occurrences: Vec<Vec<(usize, usize)>> = vec![Vec::new(); self.literals.len()];
+		let mut state = 0usize;
+
+		for (i, &raw_b) in tame.iter().enumerate()
+		{
+			let b = if self.case_insensitive { raw_b.to_ascii_lowercase() } else { raw_b };
+
+			while state != 0 && !self.nodes[state].children.contains_key(&b)
+			{
+				state = self.nodes[state].fail;
+			}
+
+			if let Some(&next) = self.nodes[state].children.get(&b)
+			{
+				state = next;
+			}
+
+			for &literal_id in &self.nodes[state].outputs
+			{
+				let len = self.literals[literal_id].len();
+				occurrences[literal_id].push((i + 1 - len, i + 1));
+			}
+		}
+
+		let mut result: Vec<usize> = Vec::new();
+
+		for pattern_index in 0..self.patterns.len()
+		{
+			if self.is_candidate(pattern_index, &occurrences, tame.len()) &&
+			   self.patterns[pattern_index].matches(tame_str)
+			{
+				result.push(pattern_index);
+			}
+		}
+
+		result
+	}
+
+	// A necessary, but not sufficient, prefilter: returns `true` unless
+	// some required literal fragment provably can't occur where the
+	// pattern needs it.  Greedily takes each fragment's earliest eligible
+	// occurrence; taking the earliest end leaves the most room for what
+	// follows, so this can't miss a real match.
+	fn is_candidate(&self, pattern_index: usize, occurrences: &[Vec<(usize, usize)>], tame_len: usize) -> bool
+	{
+		let required = &self.required[pattern_index];
+
+		if required.is_empty()
+		{
+			return true; // Nothing to prefilter; let confirmation decide.
+		}
+
+		let mut pos = 0usize;
+
+		for (k, &literal_id) in required.iter().enumerate()
+		{
+			let is_first = k == 0;
+			let is_last = k + 1 == required.len();
+
+			let found = occurrences[literal_id].iter().find(|&&(start, end)|
+				start >= pos &&
+				(!is_first || !self.anchored_start[pattern_index] || start == 0) &&
+				(!is_last || !self.anchored_end[pattern_index] || end == tame_len));
+
+			match found
+			{
+				Some(&(_, end)) => pos = end,
+				None => return false,
+			}
+		}
+
+		true
+	}
+}
+
+// Number of expansions `expand_braces` will produce before giving up, so a
+// pathological pattern (deeply nested or comma-heavy `{...}` groups) can't
+// blow up memory unbounded.
+const MAX_BRACE_EXPANSIONS: usize = 4096;
+
+// Finds the '}' matching the '{' at `open`, honoring a backslash escape
+// and nested `{...}` groups.  Returns `None` if there is no matching '}',
+// in which case callers should treat the '{' as a literal character, the
+// same fallback `parse_ascii_bracket` uses for an unterminated '['.
+fn matching_brace(wild: &[u8], open: usize) -> Option<usize>
+{
+	let mut depth = 1;
+	let mut i = open + 1;
+
+	while i < wild.len()
+	{
+		match wild[i]
+		{
+			b'\\' if i + 1 < wild.len() => i += 2,
+			b'[' =>
+			{
+				// A `,`/`{`/`}` inside a bracket class is an ordinary
+				// member, not alternation syntax; skip the whole class
+				// the same way `expand_sequence` does, so the two agree
+				// on where a class ends and a real brace begins.
+				match parse_ascii_bracket(wild, i)
+				{
+					Some((end, _negate, _members)) => i = end + 1,
+					None => i += 1,
+				}
+			}
+			b'{' => { depth += 1; i += 1; }
+			b'}' =>
+			{
+				depth -= 1;
+
+				if depth == 0
+				{
+					return Some(i);
+				}
+
+				i += 1;
+			}
+			_ => i += 1,
+		}
+	}
+
+	None
+}
+
+// Parses a brace-alternation sequence starting at `wild[pos]`, stopping at
+// the end of `wild` or at the first unescaped byte in `stop`.  Returns the
+// Cartesian expansion of every `{...}` group encountered along the way,
+// together with the position just past what was consumed.  `None` means
+// the expansion exceeded `MAX_BRACE_EXPANSIONS`.
+fn expand_sequence(wild: &[u8], mut pos: usize, stop: &[u8])
+    -> Option<(Vec<Vec<u8>>, usize)>
+{
+	let mut expansions: Vec<Vec<u8>> = vec![Vec::new()];
+
+	while pos < wild.len() && !stop.contains(&wild[pos])
+	{
+		if wild[pos] == b'\\' && pos + 1 < wild.len()
+		{
+			let escaped = wild[pos + 1];
+
+			for expansion in expansions.iter_mut()
+			{
+				expansion.push(escaped);
+			}
+
+			pos += 2;
+			continue;
+		}
+
+		if wild[pos] == b'['
+		{
+			if let Some((end, _negate, _members)) = parse_ascii_bracket(wild, pos)
+			{
+				// A `[...]` class may itself contain ',', '{', or '}'
+				// as ordinary members (e.g. `[a,b]`, `[{}]`); copy it
+				// through verbatim rather than letting its bytes be
+				// mistaken for alternation delimiters.
+				for expansion in expansions.iter_mut()
+				{
+					expansion.extend_from_slice(&wild[pos..=end]);
+				}
+
+				pos = end + 1;
+				continue;
+			}
+
+			// An unterminated '[' falls back to a literal '[', same as
+			// everywhere else `parse_ascii_bracket` is consulted.
+		}
+
+		if wild[pos] == b'{'
+		{
+			if let Some(close) = matching_brace(wild, pos)
+			{
+				let mut alternatives: Vec<Vec<u8>> = Vec::new();
+				let mut alt_pos = pos + 1;
+
+				loop
+				{
+					let (alt_expansions, next_pos) =
+						expand_sequence(wild, alt_pos, &[b',', b'}'])?;
+
+					alternatives.extend(alt_expansions);
+
+					if alternatives.len() > MAX_BRACE_EXPANSIONS
+					{
+						return None;
+					}
+
+					alt_pos = next_pos;
+
+					if wild[alt_pos] == b','
+					{
+						alt_pos += 1;
+					}
+					else
+					{
+						break; // wild[alt_pos] == b'}', i.e. `close`.
+					}
+				}
+
+				let combined_len =
+					expansions.len().checked_mul(alternatives.len())?;
+
+				if combined_len > MAX_BRACE_EXPANSIONS
+				{
+					return None;
+				}
+
+				let mut combined = Vec::with_capacity(combined_len);
+
+				for prefix in &expansions
+				{
+					for alternative in &alternatives
+					{
+						let mut next = prefix.clone();
+						next.extend_from_slice(alternative);
+						combined.push(next);
+					}
+				}
+
+				expansions = combined;
+				pos = close + 1;
+				continue;
+			}
+
+			// An unterminated '{' falls back to a literal '{'.
+		}
+
+		let literal_byte = wild[pos];
+
+		for expansion in expansions.iter_mut()
+		{
+			expansion.push(literal_byte);
+		}
+
+		pos += 1;
+	}
+
+	Some((expansions, pos))
+}
+
+// Expands every `{a,b,c}` alternation group in `wild` into the Cartesian
+// product of brace-free patterns it stands for, following the alternation
+// syntax in monotone's globish module: groups may nest, a backslash
+// escapes a literal '{', '}', or ',' so it isn't treated as a delimiter,
+// and an empty alternative (as in `{,x}`) expands to the empty string.
+// Returns `None` if expansion would exceed `MAX_BRACE_EXPANSIONS` patterns.
+pub fn expand_braces(wild: &str) -> Option<Vec<String>>
+{
+	let (expansions, _) = expand_sequence(wild.as_bytes(), 0, &[])?;
+
+	Some(expansions.into_iter()
+		.map(|bytes| String::from_utf8(bytes)
+			.expect("expand_braces only rearranges wild's own UTF-8 bytes"))
+		.collect())
+}
+
+// As `fast_wild_compare_ascii`, but first expands any `{a,b,c}`
+// alternation groups in `wild_str` (see `expand_braces`) and returns
+// `true` if `tame_str` matches any one of the expansions.
+pub fn fast_wild_compare_ascii_braces(wild_str: &str, tame_str: &str) -> bool
+{
+	fast_wild_compare_ascii_braces_with(wild_str, tame_str, Mode::NONE)
+}
+
+// As `fast_wild_compare_ascii_braces`, but accepts a `Mode` applied to
+// each expanded pattern.  Returns `false`, the same as a non-matching
+// pattern, if expansion exceeds `MAX_BRACE_EXPANSIONS`.
+pub fn fast_wild_compare_ascii_braces_with(
+    wild_str: &str,
+    tame_str: &str,
+    mode: Mode) -> bool
+{
+	match expand_braces(wild_str)
+	{
+		Some(expansions) => expansions.iter().any(
+			|expansion| fast_wild_compare_ascii_with(expansion, tame_str, mode)),
+		None => false,
+	}
+}
+
+// As `fast_wild_compare_utf8`, but first expands any `{a,b,c}`
+// alternation groups in `wild_str` (see `expand_braces`) and returns
+// `true` if `tame_str` matches any one of the expansions.
+pub fn fast_wild_compare_utf8_braces(wild_str: &str, tame_str: &str) -> bool
+{
+	fast_wild_compare_utf8_braces_with(wild_str, tame_str, Mode::NONE)
+}
+
+// As `fast_wild_compare_utf8_braces`, but accepts a `Mode` applied to each
+// expanded pattern.  Returns `false`, the same as a non-matching pattern,
+// if expansion exceeds `MAX_BRACE_EXPANSIONS`.
+pub fn fast_wild_compare_utf8_braces_with(
+    wild_str: &str,
+    tame_str: &str,
+    mode: Mode) -> bool
+{
+	match expand_braces(wild_str)
+	{
+		Some(expansions) =>
+		{
+			let tame_points = utf8_code_points(tame_str);
+
+			expansions.iter().any(|expansion| fast_wild_compare_utf8_with(
+				&utf8_code_points(expansion), &tame_points, mode))
+		}
+		None => false,
+	}
+}
+
+// A node in the fixed-prefix trie `PatternSet` uses to prune patterns
+// before running the per-pattern `Pattern::matches` core.
+struct PrefixNode
+{
+	children: std::collections::HashMap<u8, usize>,
+
+	// Indices into `PatternSet::patterns` whose fixed prefix ends exactly
+	// at this node.
+	pattern_indices: Vec<usize>,
+}
+
+impl PrefixNode
+{
+	fn new() -> PrefixNode
+	{
+		PrefixNode { children: std::collections::HashMap::new(), pattern_indices: Vec::new() }
+	}
+}
+
+// Matches one piece of text against a set of wildcard patterns compiled
+// once up front, for deployments (mail/firewall rule lists, multimaps)
+// that test many inputs against hundreds of patterns.  Unlike
+// `WildcardSet`, which indexes every literal fragment of every pattern via
+// Aho-Corasick, `PatternSet` only indexes each pattern's fixed leading
+// literal run (the bytes before its first '*', '?', or `[...]`) in a
+// plain trie, which costs less to build and suits rule lists where most
+// patterns differ in their first few characters, e.g. domain- or
+// path-prefixed rules.
+pub struct PatternSet
+{
+	patterns: Vec<Pattern>,
+	nodes: Vec<PrefixNode>, // The prefix trie; root is index 0.
+	case_insensitive: bool,
+}
+
+impl PatternSet
+{
+	// Compiles `wild_strs` with `Mode::NONE`.
+	pub fn compile(wild_strs: &[&str]) -> PatternSet
+	{
+		PatternSet::compile_with(wild_strs, Mode::NONE)
+	}
+
+	// As `compile`, but accepts a `Mode` shared by every pattern in the
+	// set.  Every pattern is confirmed through `Pattern::matches`, so
+	// `Mode::UNICODE_CASE_FOLD` is a no-op here the same way it is for
+	// `Pattern::compile_with` — it only takes effect on the `_utf8`
+	// entry points.
+	pub fn compile_with(wild_strs: &[&str], mode: Mode) -> PatternSet
+	{
+		let patterns: Vec<Pattern> = wild_strs.iter()
+			.map(|w| Pattern::compile_with(w, mode)).collect();
+		let case_insensitive = mode.contains(Mode::CASE_INSENSITIVE);
+
+		let mut nodes = vec![PrefixNode::new()];
+
+		for (index, pattern) in patterns.iter().enumerate()
+		{
+			let prefix: &[u8] = match pattern.tokens.first()
+			{
+				Some(Token::Literal(bytes)) => bytes,
+				_ => &[],
+			};
+
+			let mut state = 0usize;
+
+			for &raw_byte in prefix
+			{
+				let byte = if case_insensitive
+				{
+					raw_byte.to_ascii_lowercase()
+				}
+				else
+				{
+					raw_byte
+				};
+
+				state = match nodes[state].children.get(&byte)
+				{
+					Some(&next) => next,
+					None =>
+					{
+						let next = nodes.len();
+						nodes.push(PrefixNode::new());
+						nodes[state].children.insert(byte, next);
+						next
+					}
+				};
+			}
+
+			nodes[state].pattern_indices.push(index);
+		}
+
+		PatternSet { patterns, nodes, case_insensitive }
+	}
+
+	fn fold_byte(&self, raw_byte: u8) -> u8
+	{
+		if self.case_insensitive { raw_byte.to_ascii_lowercase() } else { raw_byte }
+	}
+
+	// Returns the indices into the original `wild_strs` of every pattern
+	// that matches `text`, in ascending order.  Walks the prefix trie once
+	// over `text`'s leading bytes, collecting a pattern's index as soon as
+	// its fixed prefix is confirmed, then confirms each candidate with
+	// `Pattern::matches`.
+	pub fn matches(&self, text: &str) -> Vec<usize>
+	{
+		let mut candidates: Vec<usize> = self.nodes[0].pattern_indices.clone();
+		let mut state = 0usize;
+
+		for &raw_byte in text.as_bytes()
+		{
+			match self.nodes[state].children.get(&self.fold_byte(raw_byte))
+			{
+				Some(&next) =>
+				{
+					state = next;
+					candidates.extend_from_slice(&self.nodes[state].pattern_indices);
+				}
+				None => break,
+			}
+		}
+
+		candidates.retain(|&index| self.patterns[index].matches(text));
+		candidates.sort_unstable();
+		candidates
+	}
+
+	// Returns `true` if any pattern in the set matches `text`, short-
+	// circuiting instead of collecting every matching index.
+	pub fn is_match(&self, text: &str) -> bool
+	{
+		let mut state = 0usize;
+
+		if self.nodes[0].pattern_indices.iter()
+			.any(|&index| self.patterns[index].matches(text))
+		{
+			return true;
+		}
+
+		for &raw_byte in text.as_bytes()
+		{
+			match self.nodes[state].children.get(&self.fold_byte(raw_byte))
+			{
+				Some(&next) =>
+				{
+					state = next;
+
+					if self.nodes[state].pattern_indices.iter()
+						.any(|&index| self.patterns[index].matches(text))
+					{
+						return true;
+					}
+				}
+				None => return false,
+			}
+		}
+
+		false
+	}
+}
@@ -27,22 +27,19 @@ const COMPARE_WILD: bool = true;
 const COMPARE_TAME: bool = true;
 const COMPARE_EMPTY: bool = true;
 const TEST_UTF8: bool = false;
+const TEST_LIBRARY_EXTENSIONS: bool = true;
 
-// File=scope variables for accumulating performance data.
-static mut U_RUST_TIME_ASCII: u128 = 0;
-static mut U_RUST_TIME_UTF8: u128 = 0;
-static mut U_CPP_TIME_FASTEST: u128 = 0;
-static mut U_CPP_TIME_PORTABLE: u128 = 0;
-
-// Standard modules for use with the String type, C/C++ functions, and 
-// performance tests.
+// Standard modules for use with the String type and C/C++ functions.
 use std::ffi::CString;
 use std::os::raw::c_char;
-use std::time::Instant;
 
 // Declarations for ASCII and UTF-8 functions for matching wildcards in Rust.
 mod fast_wild_compare;
 
+// Statistical benchmark harness used when COMPARE_PERFORMANCE is set; see
+// that module for per-case throughput reporting.
+mod benchmark;
+
 // Declarations for performance comparison with C++ versions of the algorithm
 // on which the ASCII and UTF-8 functions are baseed.
 unsafe extern "C" {
@@ -65,43 +62,30 @@ fn test(tame_string: String, wild_string: String,
 {
 	if COMPARE_PERFORMANCE
 	{
-		// Get execution times for our two Rust routines.
-		let timer_1 = Instant::now();
-
+		// Throughput numbers live in the benchmark module now, so this
+		// path only cross-validates the two Rust routines and the two
+		// C/C++ routines against each other.
 		if b_expected_result != fast_wild_compare::fast_wild_compare_ascii(
 			&wild_string, &tame_string)
 		{
 			return false;
 		}
 
-		unsafe  // For the sake of avoiding piles of passed parameters.
-		{
-			U_RUST_TIME_ASCII += timer_1.elapsed().as_nanos();
-		}
-
-		let timer_2 = Instant::now();
-
-		// Allocate array-style memory and initialize with each input String's 
+		// Allocate array-style memory and initialize with each input String's
 		// 32-bit UTF-8 code points.
 		//
-		// A memory allocation failure can be associated with a panic.  In a 
-		// situation involving many calls to this routine, arrangements to 
-		// catch allocation failures may be placed around that entire set of 
+		// A memory allocation failure can be associated with a panic.  In a
+		// situation involving many calls to this routine, arrangements to
+		// catch allocation failures may be placed around that entire set of
 		// calls.
 		//
 		if b_expected_result != fast_wild_compare::fast_wild_compare_utf8(
-		        wild_string.chars().collect::<Vec<char>>().into_boxed_slice(),
-		        tame_string.chars().collect::<Vec<char>>().into_boxed_slice())
+		        &fast_wild_compare::utf8_code_points(&wild_string),
+		        &fast_wild_compare::utf8_code_points(&tame_string))
 		{
 			return false;
 		}
 
-		unsafe
-		{
-			U_RUST_TIME_UTF8 += timer_2.elapsed().as_nanos();
-		}
-
-		// For comparison, get execution times for the C/C++ versions.
 		unsafe
 		{
 			let c_wild = CString::new(wild_string).expect(
@@ -111,41 +95,33 @@ fn test(tame_string: String, wild_string: String,
 			let c_wild_ptr: *mut c_char = c_wild.into_raw();
 			let c_tame_ptr: *mut c_char = c_tame.into_raw();
 
-			let timer_3 = Instant::now();
-
 			if b_expected_result != FastWildCompare(
 			       c_wild_ptr, c_tame_ptr)
 			{
 				return false;
 			}
 
-			U_CPP_TIME_FASTEST += timer_3.elapsed().as_nanos();
-
-			let timer_4 = Instant::now();
-
 			if b_expected_result != FastWildComparePortable(
 			       c_wild_ptr, c_tame_ptr)
 			{
 				return false;
 			}
-
-			U_CPP_TIME_PORTABLE += timer_4.elapsed().as_nanos();
 		}
 	}
 	else if TEST_UTF8
 	{
-		// Case-insensitive matching:
-		// Allocate array-style memory and initialize with each input String's 
-		// lowercased 32-bit UTF-8 code points.
+		// Case-insensitive matching: fold each code point as the
+		// comparison happens instead of lowercasing whole strings ahead
+		// of time, so a character whose full lowercasing spans more than
+		// one code point can't desynchronize the wild/tame slices.
 		//
-		// A memory allocation failure can be associated with a panic.  See 
+		// A memory allocation failure can be associated with a panic.  See
 		// above comment regarding catching that situation in production code.
 		//
-	    if b_expected_result != fast_wild_compare::fast_wild_compare_utf8(
-			       wild_string.to_lowercase(
-		                  ).chars().collect::<Vec<char>>().into_boxed_slice(),
-		           tame_string.to_lowercase(
-		                  ).chars().collect::<Vec<char>>().into_boxed_slice())
+	    if b_expected_result != fast_wild_compare::fast_wild_compare_utf8_with(
+			       &fast_wild_compare::utf8_code_points(&wild_string),
+		           &fast_wild_compare::utf8_code_points(&tame_string),
+		           fast_wild_compare::Mode::UNICODE_CASE_FOLD)
 		{
 			return false;
 		}
@@ -611,6 +587,14 @@ fn test_utf8()
 	b_all_passed &= test(
 	    "ĞœĞ½Ğµ Ğ½ÑƒĞ¶Ğ½Ğ¾ Ğ²Ñ‹ÑƒÑ‡Ğ¸Ñ‚ÑŒ Ñ€ÑƒÑÑĞºĞ¸Ğ¹ ÑĞ·Ñ‹Ğº, Ñ‡Ñ‚Ğ¾Ğ±Ñ‹ Ğ»ÑƒÑ‡ÑˆĞµ Ğ¾Ñ†ĞµĞ½Ğ¸Ñ‚ÑŒ ĞŸÑƒÑˆĞºĞ¸Ğ½Ğ°.".into(), 
 	    "ĞœĞ½Ğµ Ğ½ÑƒĞ¶Ğ½Ğ¾ Ğ²Ñ‹ÑƒÑ‡Ğ¸Ñ‚ÑŒ * ÑĞ·Ñ‹Ğº, Ñ‡Ñ‚Ğ¾Ğ±Ñ‹ Ğ»ÑƒÑ‡ÑˆĞµ Ğ¾Ñ†ĞµĞ½Ğ¸Ñ‚ÑŒ *.".into(), true);
+	// Mode::UNICODE_CASE_FOLD extends case-insensitive matching beyond
+	// ASCII, so the lower-cased wild string above also matches the
+	// mixed-case tame string regardless of script.
+	b_all_passed &= fast_wild_compare::fast_wild_compare_utf8_with(
+	    &fast_wild_compare::utf8_code_points("ğœğ½ğµ ğ½ñƒğ¶ğ½ğ¾ ğ²ñ‹ñƒñ‡ğ¸ñ‚ñœ * ñğ·ñ‹ğº, ñ‡ñ‚ğ¾ğ±ñ‹ ğ»ñƒñ‡ñˆğµ ğ¾ñ†ğµğ½ğ¸ñ‚ñœ *."),
+	    &fast_wild_compare::utf8_code_points(
+	        "ĞœĞ½Ğµ Ğ½ÑƒĞ¶Ğ½Ğ¾ Ğ²Ñ‹ÑƒÑ‡Ğ¸Ñ‚ÑŒ Ñ€ÑƒÑÑĞºĞ¸Ğ¹ ÑĞ·Ñ‹Ğº, Ñ‡Ñ‚Ğ¾Ğ±Ñ‹ Ğ»ÑƒÑ‡ÑˆĞµ Ğ¾Ñ†ĞµĞ½Ğ¸Ñ‚ÑŒ ĞŸÑƒÑˆĞºĞ¸Ğ½Ğ°."),
+	    fast_wild_compare::Mode::UNICODE_CASE_FOLD) == true;
 	b_all_passed &= test(
 	    "×× ×™ ×¦×¨×™×š ×œ×œ××•×“ ×× ×’×œ×™×ª ×›×“×™ ×œ×”×¢×¨×™×š ××ª ×’×™× ×¡×‘×¨×’".into(), 
 	    " ×× ×™ ×¦×¨×™×š ×œ×œ××•×“ ×× ×’×œ×™×ª ×›×“×™ ×œ×”×¢×¨×™×š ××ª ???????".into(), false);
@@ -643,7 +627,386 @@ fn test_utf8()
 }
 
 
-// Entry point for the Rust executable.  Performance findings (if any) are 
+// Correctness tests for `Mode::CASE_INSENSITIVE` on the ASCII routine,
+// invoked directly rather than through `test()` since `test()` has no way
+// to pass a `Mode` through to the performance-comparison or plain-ASCII
+// branches it already covers.
+//
+fn test_case_insensitive_mode()
+{
+    let mut b_all_passed: bool = true;
+
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("ABC",
+        "abc", fast_wild_compare::Mode::CASE_INSENSITIVE) == true;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("a*C",
+        "abc", fast_wild_compare::Mode::CASE_INSENSITIVE) == true;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("A?C",
+        "abc", fast_wild_compare::Mode::CASE_INSENSITIVE) == true;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("ABD",
+        "abc", fast_wild_compare::Mode::CASE_INSENSITIVE) == false;
+    // Without the flag, case still matters.
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("ABC",
+        "abc", fast_wild_compare::Mode::NONE) == false;
+
+    if b_all_passed
+    {
+        println!("Passed case-insensitive mode tests");
+    }
+    else
+    {
+        println!("Failed case-insensitive mode tests");
+    }
+}
+
+// Correctness tests for `Mode::BACKSLASH_ESCAPES`, which lets a wild
+// string match a literal '*', '?', or '[' by escaping it with '\'.
+//
+fn test_backslash_escapes()
+{
+    let mut b_all_passed: bool = true;
+
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("a\\*b",
+        "a*b", fast_wild_compare::Mode::BACKSLASH_ESCAPES) == true;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("a\\*b",
+        "axb", fast_wild_compare::Mode::BACKSLASH_ESCAPES) == false;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("a\\?b",
+        "a?b", fast_wild_compare::Mode::BACKSLASH_ESCAPES) == true;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("a\\[b",
+        "a[b", fast_wild_compare::Mode::BACKSLASH_ESCAPES) == true;
+    // A trailing backslash is a literal backslash.
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("a\\",
+        "a\\", fast_wild_compare::Mode::BACKSLASH_ESCAPES) == true;
+    // Without the flag, '\' has no special meaning and '*' still matches
+    // any run of characters.
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("a\\*b",
+        "axb", fast_wild_compare::Mode::NONE) == false;
+
+    if b_all_passed
+    {
+        println!("Passed backslash escape tests");
+    }
+    else
+    {
+        println!("Failed backslash escape tests");
+    }
+}
+
+// Correctness tests for `Mode::NO_MATCH_SLASH_LITERAL`, the path-aware
+// globbing mode where a lone '*'/'?' stop at '/' but a run of two or more
+// ('**') may cross it.
+//
+fn test_path_aware_globbing()
+{
+    let mut b_all_passed: bool = true;
+
+    // A lone '*' may not cross a '/'.
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("a*b",
+        "a/b", fast_wild_compare::Mode::NO_MATCH_SLASH_LITERAL) == false;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("a*b",
+        "axb", fast_wild_compare::Mode::NO_MATCH_SLASH_LITERAL) == true;
+    // '**' may cross a '/'.
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("a**b",
+        "a/x/b", fast_wild_compare::Mode::NO_MATCH_SLASH_LITERAL) == true;
+    // '?' may not match a '/' either.
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("a?b",
+        "a/b", fast_wild_compare::Mode::NO_MATCH_SLASH_LITERAL) == false;
+    // Without the flag, '*' crosses '/' freely.
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("a*b",
+        "a/b", fast_wild_compare::Mode::NONE) == true;
+    // Regression: a '*' with no tame bytes left to backtrack into must
+    // report no match rather than reading past the end of a shorter tame
+    // string (see Pattern::matches's backtrack fallback).
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_with("a*b",
+        "a", fast_wild_compare::Mode::NO_MATCH_SLASH_LITERAL) == false;
+
+    if b_all_passed
+    {
+        println!("Passed path-aware globbing tests");
+    }
+    else
+    {
+        println!("Failed path-aware globbing tests");
+    }
+}
+
+// Correctness tests for `WildcardSet`, the Aho-Corasick-prefiltered
+// multi-pattern matcher.
+//
+fn test_wildcard_set()
+{
+    let mut b_all_passed: bool = true;
+
+    let set = fast_wild_compare::WildcardSet::compile(
+        &["*.txt", "log-*.csv", "readme*"]);
+
+    b_all_passed &= set.matches("notes.txt") == vec![0];
+    b_all_passed &= set.matches("log-2024.csv") == vec![1];
+    b_all_passed &= set.matches("readme.md") == vec![2];
+    b_all_passed &= set.matches("notes.md").is_empty();
+
+    // A name can satisfy more than one pattern in the set.
+    let overlapping = fast_wild_compare::WildcardSet::compile(
+        &["*.txt", "note*"]);
+    b_all_passed &= overlapping.matches("notes.txt") == vec![0, 1];
+
+    // Case-insensitive mode applies to every pattern in the set.
+    let folded = fast_wild_compare::WildcardSet::compile_with(&["*.TXT"],
+        fast_wild_compare::Mode::CASE_INSENSITIVE);
+    b_all_passed &= folded.matches("notes.txt") == vec![0];
+
+    // Regression: Mode::UNICODE_CASE_FOLD only affects the UTF-8 routines
+    // (see Pattern::compile_with's doc comment), so it's a documented
+    // no-op here, where every pattern confirms through Pattern::matches.
+    let unicode_folded = fast_wild_compare::WildcardSet::compile_with(
+        &["*ΣΣ*"], fast_wild_compare::Mode::UNICODE_CASE_FOLD);
+    b_all_passed &= unicode_folded.matches("xxσσxx").is_empty();
+
+    if b_all_passed
+    {
+        println!("Passed WildcardSet tests");
+    }
+    else
+    {
+        println!("Failed WildcardSet tests");
+    }
+}
+
+// Correctness tests for POSIX-style `[...]` bracket character classes,
+// including ranges and negation, on both the ASCII and UTF-8 routines.
+//
+fn test_bracket_classes()
+{
+    let mut b_all_passed: bool = true;
+
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii("[abc]",
+        "b") == true;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii("[abc]",
+        "d") == false;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii("[a-z]",
+        "m") == true;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii("[a-z]",
+        "M") == false;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii("[!a-z]",
+        "M") == true;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii("[^a-z]",
+        "m") == false;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii("file[0-9].txt",
+        "file5.txt") == true;
+
+    let wild_points = fast_wild_compare::utf8_code_points("[α-ω]");
+    let tame_points = fast_wild_compare::utf8_code_points("λ");
+    b_all_passed &= fast_wild_compare::fast_wild_compare_utf8(&wild_points,
+        &tame_points) == true;
+
+    if b_all_passed
+    {
+        println!("Passed bracket class tests");
+    }
+    else
+    {
+        println!("Failed bracket class tests");
+    }
+}
+
+// Correctness tests for brace alternation (`{foo,bar}`), implemented as a
+// pre-pass that expands to a `Vec<String>` of brace-free patterns and
+// matches if any expansion matches.
+//
+fn test_brace_alternation()
+{
+    let mut b_all_passed: bool = true;
+
+    b_all_passed &= fast_wild_compare::expand_braces("a{b,c}d") ==
+        Some(vec!["abd".to_string(), "acd".to_string()]);
+    b_all_passed &= fast_wild_compare::expand_braces("no braces here") ==
+        Some(vec!["no braces here".to_string()]);
+
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_braces(
+        "*.{png,jpg,gif}", "photo.jpg") == true;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_braces(
+        "*.{png,jpg,gif}", "photo.bmp") == false;
+
+    b_all_passed &= fast_wild_compare::fast_wild_compare_utf8_braces(
+        "{σ,ς}", "ς") == true;
+
+    // Regression: a ',' or brace inside a `[...]` bracket class is a
+    // class member, not an alternation delimiter, so the class must
+    // pass through `expand_braces` untouched.
+    b_all_passed &= fast_wild_compare::expand_braces("{[a,b],x}") ==
+        Some(vec!["[a,b]".to_string(), "x".to_string()]);
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_braces(
+        "{[a,b],x}", "a") == true;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_braces(
+        "{[a,b],x}", "[a") == false;
+
+    // Regression: an unterminated '{' whose only following '}' is
+    // itself swallowed as a bracket-class member (so there's no real
+    // brace to match) must not panic; matching_brace has to see
+    // brackets the same way expand_sequence does, or the two disagree
+    // on where a class ends and a brace begins.
+    b_all_passed &= fast_wild_compare::expand_braces("!{{[}]/^]").is_some();
+
+    // Regression: this public entry point calls straight into
+    // Pattern::matches, so a dangling '*' expansion under
+    // Mode::NO_MATCH_SLASH_LITERAL must report no match rather than
+    // reading past the end of a shorter tame string (see chunk0-5's
+    // bounds fix, which this entry point inherits without code changes
+    // of its own).
+    b_all_passed &= fast_wild_compare::fast_wild_compare_ascii_braces_with(
+        "a{x,y}*b", "a",
+        fast_wild_compare::Mode::NO_MATCH_SLASH_LITERAL) == false;
+
+    if b_all_passed
+    {
+        println!("Passed brace alternation tests");
+    }
+    else
+    {
+        println!("Failed brace alternation tests");
+    }
+}
+
+// Correctness tests for `PatternSet`, the fixed-prefix-trie-pruned
+// multi-pattern matcher.
+//
+fn test_pattern_set()
+{
+    let mut b_all_passed: bool = true;
+
+    let set = fast_wild_compare::PatternSet::compile(
+        &["log-*.txt", "log-*.csv", "readme*"]);
+
+    b_all_passed &= set.matches("log-2024.txt") == vec![0];
+    b_all_passed &= set.matches("log-2024.csv") == vec![1];
+    b_all_passed &= set.is_match("readme.md") == true;
+    b_all_passed &= set.is_match("notes.md") == false;
+
+    let folded = fast_wild_compare::PatternSet::compile_with(&["LOG-*.TXT"],
+        fast_wild_compare::Mode::CASE_INSENSITIVE);
+    b_all_passed &= folded.is_match("log-2024.txt") == true;
+
+    // Regression: PatternSet::matches/is_match call straight into
+    // Pattern::matches, so a dangling '*' under Mode::NO_MATCH_SLASH_LITERAL
+    // must report no match rather than reading past the end of a shorter
+    // tame string (see chunk0-5's bounds fix, which this type inherits
+    // without code changes of its own).
+    let path_aware = fast_wild_compare::PatternSet::compile_with(&["a*b"],
+        fast_wild_compare::Mode::NO_MATCH_SLASH_LITERAL);
+    b_all_passed &= path_aware.is_match("a") == false;
+
+    // Regression: Mode::UNICODE_CASE_FOLD only affects the UTF-8 routines
+    // (see Pattern::compile_with's doc comment), so it's a documented
+    // no-op here, where every pattern confirms through Pattern::matches.
+    let unicode_folded = fast_wild_compare::PatternSet::compile_with(
+        &["*ΣΣ*"], fast_wild_compare::Mode::UNICODE_CASE_FOLD);
+    b_all_passed &= unicode_folded.is_match("xxσσxx") == false;
+
+    if b_all_passed
+    {
+        println!("Passed PatternSet tests");
+    }
+    else
+    {
+        println!("Failed PatternSet tests");
+    }
+}
+
+// Correctness tests for `fast_wild_capture`/`fast_wild_capture_utf8`,
+// which return the byte range consumed by each '*'/'?' in pattern order.
+//
+fn test_capture()
+{
+    let mut b_all_passed: bool = true;
+
+    let tame = "log-2024-07.txt";
+    let captures = fast_wild_compare::fast_wild_capture("log-*.txt", tame);
+    b_all_passed &= captures.as_ref().map(|ranges|
+        ranges.iter().map(|r| &tame[r.clone()]).collect::<Vec<_>>()) ==
+        Some(vec!["2024-07"]);
+
+    b_all_passed &= fast_wild_compare::fast_wild_capture("a?c", "abc") ==
+        Some(vec![1..2]);
+    b_all_passed &= fast_wild_compare::fast_wild_capture("abc", "abd").is_none();
+
+    let utf8_wild = fast_wild_compare::utf8_code_points("*-*.log");
+    let utf8_tame = "ca\u{00a9}fé-2024.log";
+    let utf8_captures = fast_wild_compare::fast_wild_capture_utf8(
+        &utf8_wild, utf8_tame);
+    b_all_passed &= utf8_captures.as_ref().map(|ranges|
+        ranges.iter().map(|r| &utf8_tame[r.clone()]).collect::<Vec<_>>()) ==
+        Some(vec!["ca\u{00a9}fé", "2024"]);
+
+    // Regression: fast_wild_capture_utf8_with has its own backtracking
+    // loop over code points (it doesn't share code with the ascii
+    // Pattern), and independently needed the same bounds-before-index
+    // fix: a dangling '*' under Mode::NO_MATCH_SLASH_LITERAL must report
+    // no match rather than reading past the end of a shorter tame string.
+    let regression_wild = fast_wild_compare::utf8_code_points("*bba");
+    b_all_passed &= fast_wild_compare::fast_wild_capture_utf8_with(
+        &regression_wild, "",
+        fast_wild_compare::Mode::NO_MATCH_SLASH_LITERAL).is_none();
+
+    if b_all_passed
+    {
+        println!("Passed capture tests");
+    }
+    else
+    {
+        println!("Failed capture tests");
+    }
+}
+
+// Correctness tests for `Mode::UNICODE_CASE_FOLD`, invoked from main()
+// under TEST_LIBRARY_EXTENSIONS rather than via test_utf8() (gated behind
+// TEST_UTF8, which is false) so this actually runs.  Exercises a
+// non-Cyrillic bicameral script, Greek, including a case a
+// char::to_lowercase()-based fold can't get right on its own: final sigma
+// 'ς' (U+03C2), which must fold the same as medial sigma 'Σ'/'σ'.
+//
+fn test_unicode_case_fold()
+{
+    let mut b_all_passed: bool = true;
+
+    b_all_passed &= fast_wild_compare::fast_wild_compare_utf8_with(
+        &fast_wild_compare::utf8_code_points("λόγος"),
+        &fast_wild_compare::utf8_code_points("ΛΌΓΟΣ"),
+        fast_wild_compare::Mode::UNICODE_CASE_FOLD) == true;
+
+    // Final sigma only appears at the end of a word ('ς'), while medial
+    // sigma ('σ') appears elsewhere; both must fold to the same value as
+    // capital sigma ('Σ').
+    b_all_passed &= fast_wild_compare::fast_wild_compare_utf8_with(
+        &fast_wild_compare::utf8_code_points("ΟΔΥΣΣΕΥΣ"),
+        &fast_wild_compare::utf8_code_points("οδυσσευς"),
+        fast_wild_compare::Mode::UNICODE_CASE_FOLD) == true;
+    b_all_passed &= fast_wild_compare::fast_wild_compare_utf8_with(
+        &fast_wild_compare::utf8_code_points("*ς"),
+        &fast_wild_compare::utf8_code_points("ΛΌΓΟΣ"),
+        fast_wild_compare::Mode::UNICODE_CASE_FOLD) == true;
+
+    // Without the flag, script-specific case distinctions are preserved.
+    b_all_passed &= fast_wild_compare::fast_wild_compare_utf8(
+        &fast_wild_compare::utf8_code_points("λόγος"),
+        &fast_wild_compare::utf8_code_points("ΛΌΓΟΣ")) == false;
+
+    // Mode::UNICODE_CASE_FOLD is only consulted by char_eq/utf8_class_contains,
+    // so it's a documented no-op on the ASCII/byte engine (Pattern and
+    // everything built on it: fast_wild_compare_ascii_with, WildcardSet,
+    // PatternSet) rather than silently folding non-ASCII literal bytes.
+    b_all_passed &= fast_wild_compare::Pattern::compile_with("*ΣΣ*",
+        fast_wild_compare::Mode::UNICODE_CASE_FOLD).matches("xxσσxx") == false;
+
+    if b_all_passed
+    {
+        println!("Passed Unicode case fold tests");
+    }
+    else
+    {
+        println!("Failed Unicode case fold tests");
+    }
+}
+
+// Entry point for the Rust executable.  Performance findings (if any) are
 // displayed here, once all tests have run.
 //
 fn main()
@@ -669,50 +1032,21 @@ fn main()
 		test_utf8();
 	}
 
+	if TEST_LIBRARY_EXTENSIONS
+	{
+		test_case_insensitive_mode();
+		test_backslash_escapes();
+		test_path_aware_globbing();
+		test_wildcard_set();
+		test_bracket_classes();
+		test_brace_alternation();
+		test_pattern_set();
+		test_capture();
+		test_unicode_case_fold();
+	}
+
 	if COMPARE_PERFORMANCE
 	{
-		unsafe  // Timings have been accumulated via mutable file-scope data.
-		{
-			let base: f64 = 10.0;
-			let f_cumulative_time_ascii_version: f64 = 
-			      (U_RUST_TIME_ASCII as f64 / base.powf(9.0)).round() * 
-				      base.powf(3.0);
-			let f_cumulative_time_utf8_version: f64 = 
-			      (U_RUST_TIME_UTF8 as f64 / base.powf(9.0)).round() * 
-				       base.powf(3.0);
-			let f_cumulative_time_fwc_cpp: f64 = 
-			      (U_CPP_TIME_FASTEST as f64 / base.powf(9.0)).round() * 
-				       base.powf(3.0);		 
-			let f_cumulative_time_fwcp_cpp: f64 = 
-			      (U_CPP_TIME_PORTABLE as f64 / base.powf(9.0)).round() * 
-				       base.powf(3.0);
-
-			// Represent the rounded timings in seconds, using integer values.
-			let u_utf8_version_seconds = 
-			    (f_cumulative_time_utf8_version as u64) / 1000;
-			let u_ascii_version_seconds = 
-			    (f_cumulative_time_ascii_version as u64) / 1000;
-			let u_fwcp_cpp_seconds = 
-			    (f_cumulative_time_fwcp_cpp as u64) / 1000;
-			let u_fwc_cpp_seconds = 
-			    (f_cumulative_time_fwc_cpp as u64) / 1000;
-
-			// Show the timing results.
-			println!(
-				"fast_wild_compare_utf8 - \
-				Rust version providing UTF-8 enablement: {:?} seconds", 
-				u_utf8_version_seconds);
-			println!(
-				"fast_wild_compare_ascii - \
-				Light-weight Rust version for string slices: {:?} seconds", 
-				u_ascii_version_seconds);
-			println!(
-				"FastWildComparePortable - \
-				C++ equivalent of fast_wild_compare_ascii: {:?} seconds", 
-				u_fwcp_cpp_seconds);
-			println!("FastWildCompare - \
-			Optimized C++ pointer-based algorithm: {:?} seconds", 
-				u_fwc_cpp_seconds);
-		}
-	}	
+		benchmark::run_benchmarks(benchmark::CASES);
+	}
 }